@@ -0,0 +1,186 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use multiversx_sc_scenario::imports::*;
+use std::sync::Once;
+
+const OWNER_ADDRESS: TestAddress = TestAddress::new("owner");
+const SC_ADDRESS: TestSCAddress = TestSCAddress::new("on-chain-claim");
+const CODE_PATH: MxscPath = MxscPath::new("output/on-chain-claim.mxsc.json");
+const TOKEN_ID: &str = "REPAIR-123456";
+const TOKEN: TestTokenIdentifier = TestTokenIdentifier::new(TOKEN_ID);
+
+const ENDPOINTS: &[&str] = &[
+    "claim",
+    "claimAndRepair",
+    "updateState",
+    "getAddressInfo",
+    "canBeRepaired",
+    "getRepairStreakPayment",
+    "hasRole",
+    "getRoleHolders",
+    "pause",
+    "unpause",
+    "isPaused",
+    "getAdminActionCount",
+    "openSeason",
+    "closeSeason",
+    "getSeasons",
+    "getCurrentSeason",
+    "getSeasonStats",
+    "claimFor",
+    "authorizeDelegate",
+    "revokeDelegate",
+    "addAllowedContract",
+    "removeAllowedContract",
+    "isDelegate",
+    "isAllowedContract",
+    "getDelegates",
+    "getBeneficiaries",
+    "getAllowedContracts",
+    "claimWithSignature",
+    "getNextClaimNonce",
+    "proposeConfigChange",
+    "executeConfigChange",
+    "cancelConfigChange",
+    "getTimelockEpochs",
+    "getPendingConfigChanges",
+    "getConfigChange",
+    "getMaxRepairGap",
+    "setStateOverrideQuorum",
+    "approveStateOverride",
+    "cancelStateOverride",
+    "getPendingStateOverrides",
+    "getStateOverrideQuorum",
+    "getStateOverrideExpiryEpochs",
+    "getStateOverrideApprovals",
+    "setClaimHistorySize",
+    "getClaimHistory",
+    "getClaimHistorySize",
+    "getClaimBitmap",
+    "issueBadgeCollection",
+    "setBadgeTiers",
+    "getBadge",
+    "getBadgeTiers",
+    "getBadgeTokenId",
+    "getBadgeNonce",
+    "hasMinStreak",
+    "hasClaimedInLastEpochs",
+    "isActiveClaimer",
+    "claimWithReferrer",
+    "setReferralMilestones",
+    "getReferees",
+    "getRefereeCount",
+    "getReferralMilestones",
+    "getReferrer",
+    "getReferralPoints",
+    "getRepairPaymentSink",
+];
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    /// Written through updateState before the call, to reach the streak math with extreme values.
+    address_info: Option<(u64, u64, u64, u64)>,
+    epoch: u64,
+    endpoint: u8,
+    payment: Option<u8>,
+    arguments: Vec<Vec<u8>>,
+}
+
+/// The debugger VM signals contract errors by panicking with a non-string payload, which it catches itself.
+/// Those are let through, while string panics, from the contract code or from the checks below, still abort.
+fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let fuzzer_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |panic_info| {
+            let payload = panic_info.payload();
+            if payload.is::<String>() || payload.is::<&str>() {
+                fuzzer_hook(panic_info);
+            }
+        }));
+    });
+}
+
+fn setup(epoch: u64) -> ScenarioWorld {
+    let mut world = ScenarioWorld::new();
+    world.register_contract(CODE_PATH, on_chain_claim::ContractBuilder);
+    world
+        .account(OWNER_ADDRESS)
+        .nonce(1)
+        .esdt_balance(TOKEN, 1_000);
+    world.current_block().block_epoch(epoch);
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .raw_deploy()
+        .argument(&TOKEN_ID)
+        .argument(&0u64)
+        .code(CODE_PATH)
+        .new_address(SC_ADDRESS)
+        .run();
+    world.set_esdt_local_roles(SC_ADDRESS, TOKEN_ID.as_bytes(), &[EsdtLocalRole::Burn]);
+
+    world
+}
+
+/// Failed transactions are expected, as long as the contract rejected them through `require!` or argument decoding
+/// rather than by panicking.
+fn check_outcome(status: u64, message: &str) {
+    assert!(
+        !message.starts_with("panic occurred"),
+        "contract panicked with status {status}: {message}"
+    );
+}
+
+fuzz_target!(|input: Input| {
+    install_panic_hook();
+    let mut world = setup(input.epoch);
+
+    if let Some((current_streak, last_epoch_claimed, total_epochs_claimed, best_streak)) =
+        input.address_info
+    {
+        let (status, message) = world
+            .tx()
+            .from(OWNER_ADDRESS)
+            .to(SC_ADDRESS)
+            .raw_call("updateState")
+            .argument(&OWNER_ADDRESS.to_address())
+            .argument(&current_streak)
+            .argument(&last_epoch_claimed)
+            .argument(&total_epochs_claimed)
+            .argument(&best_streak)
+            .returns(ReturnsStatus)
+            .returns(ReturnsMessage)
+            .run();
+        check_outcome(status, &message);
+    }
+
+    let endpoint = ENDPOINTS[input.endpoint as usize % ENDPOINTS.len()];
+    let mut arguments = ManagedArgBuffer::<StaticApi>::new();
+    for argument in &input.arguments {
+        arguments.push_arg_raw(ManagedBuffer::new_from_bytes(argument));
+    }
+
+    let tx = world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .raw_call(endpoint)
+        .arguments_raw(arguments);
+    let (status, message) = match input.payment {
+        Some(amount) => tx
+            .payment((
+                TokenIdentifier::from(TOKEN),
+                0,
+                BigUint::from(amount as u64),
+            ))
+            .returns(ReturnsStatus)
+            .returns(ReturnsMessage)
+            .run(),
+        None => tx.returns(ReturnsStatus).returns(ReturnsMessage).run(),
+    };
+    check_outcome(status, &message);
+});
@@ -0,0 +1,72 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use on_chain_claim::{AddressInfo, ClaimOutcome, StreakError};
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    address_info: Option<(u64, u64, u64, u64)>,
+    current_epoch: u64,
+    max_missed_epochs: u64,
+}
+
+fuzz_target!(|input: Input| {
+    let previous = input.address_info.map(
+        |(current_streak, last_epoch_claimed, total_epochs_claimed, best_streak)| {
+            AddressInfo::new(
+                current_streak,
+                last_epoch_claimed,
+                total_epochs_claimed,
+                best_streak,
+            )
+        },
+    );
+
+    match AddressInfo::claim(previous.as_ref(), input.current_epoch) {
+        Ok((address_info, outcome)) => {
+            check_claimed(previous.as_ref(), &address_info, input.current_epoch);
+            match (&previous, outcome) {
+                (None, ClaimOutcome::Started) => {
+                    assert_eq!(
+                        address_info,
+                        AddressInfo::new_with_epoch(input.current_epoch)
+                    )
+                }
+                (Some(_), ClaimOutcome::Extended) => assert!(address_info.current_streak >= 1),
+                (Some(_), ClaimOutcome::Reset) => assert_eq!(address_info.current_streak, 1),
+                _ => panic!("unexpected outcome {outcome:?}"),
+            }
+        }
+        Err(err) => {
+            assert_eq!(err, StreakError::EpochAlreadyClaimed);
+            assert!(input.current_epoch <= previous.as_ref().unwrap().last_epoch_claimed);
+        }
+    }
+
+    match AddressInfo::claim_and_repair(
+        previous.as_ref(),
+        input.current_epoch,
+        input.max_missed_epochs,
+    ) {
+        Ok((address_info, missed_epochs)) => {
+            check_claimed(previous.as_ref(), &address_info, input.current_epoch);
+            assert!(missed_epochs > 0 && missed_epochs <= input.max_missed_epochs);
+        }
+        Err(StreakError::NothingToRepair) => assert!(previous.is_none()),
+        Err(StreakError::RepairNotAllowed) => assert!(!previous
+            .unwrap()
+            .can_be_repaired(input.current_epoch, input.max_missed_epochs)),
+        Err(err) => panic!("unexpected error {err:?}"),
+    }
+});
+
+fn check_claimed(previous: Option<&AddressInfo>, address_info: &AddressInfo, current_epoch: u64) {
+    assert_eq!(address_info.last_epoch_claimed, current_epoch);
+    assert!(address_info.best_streak >= address_info.current_streak);
+
+    if let Some(previous) = previous {
+        assert!(address_info.total_epochs_claimed >= previous.total_epochs_claimed);
+        assert!(address_info.best_streak >= previous.best_streak);
+    }
+}
@@ -0,0 +1,461 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use multiversx_sc_snippets::imports::*;
+use multiversx_sc_snippets::sdk::data::transaction::{ApiLogs, Events, TransactionOnNetwork};
+use on_chain_claim::{events::AddressInfoUpdate, AddressInfo};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClaimEventKind {
+    Claim,
+    DelegatedClaim,
+    SignedClaim,
+    ClaimAndRepair,
+    UpdateState,
+    SetRepairPayment,
+}
+
+impl ClaimEventKind {
+    pub fn from_identifier(identifier: &[u8]) -> Option<Self> {
+        match identifier {
+            b"new_claim" => Some(ClaimEventKind::Claim),
+            b"new_delegated_claim" => Some(ClaimEventKind::DelegatedClaim),
+            b"new_signed_claim" => Some(ClaimEventKind::SignedClaim),
+            b"new_claim_and_repair" => Some(ClaimEventKind::ClaimAndRepair),
+            b"new_update_state" => Some(ClaimEventKind::UpdateState),
+            b"new_set_repair_payment" => Some(ClaimEventKind::SetRepairPayment),
+            _ => None,
+        }
+    }
+}
+
+/// Serializable mirror of the contract's `AddressInfo`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressInfoRecord {
+    pub current_streak: u64,
+    pub last_epoch_claimed: u64,
+    pub total_epochs_claimed: u64,
+    pub best_streak: u64,
+}
+
+impl From<AddressInfo> for AddressInfoRecord {
+    fn from(address_info: AddressInfo) -> Self {
+        AddressInfoRecord {
+            current_streak: address_info.current_streak,
+            last_epoch_claimed: address_info.last_epoch_claimed,
+            total_epochs_claimed: address_info.total_epochs_claimed,
+            best_streak: address_info.best_streak,
+        }
+    }
+}
+
+impl From<AddressInfoRecord> for AddressInfo {
+    fn from(record: AddressInfoRecord) -> Self {
+        AddressInfo::new(
+            record.current_streak,
+            record.last_epoch_claimed,
+            record.total_epochs_claimed,
+            record.best_streak,
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaymentRecord {
+    pub token_identifier: String,
+    pub token_nonce: u64,
+    pub amount: String,
+}
+
+/// A contract event, decoded from the transaction logs.
+///
+/// Events emitted before the contract started publishing the epoch and previous state only carry
+/// the resulting address info; for those, the epoch is taken from the transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClaimEvent {
+    /// The transaction, or the smart contract result, whose logs hold the event.
+    pub tx_hash: String,
+    pub epoch: u64,
+    pub timestamp: u64,
+    pub kind: ClaimEventKind,
+    pub address: Option<Bech32Address>,
+    pub address_info: Option<AddressInfoRecord>,
+    #[serde(default)]
+    pub previous_address_info: Option<AddressInfoRecord>,
+    #[serde(default)]
+    pub missed_epochs: Option<u64>,
+    #[serde(default)]
+    pub payment: Option<PaymentRecord>,
+    /// The address that claimed on behalf of `address`: the delegate or the relayer of a signed claim.
+    #[serde(default)]
+    pub delegate: Option<Bech32Address>,
+}
+
+/// Decodes all the contract events found in the logs of a transaction or smart contract result.
+///
+/// Events emitted by other contracts, or not known by this contract, are skipped.
+/// Events that can't be decoded are reported and skipped, without stopping the indexing.
+pub fn decode_transaction_events(
+    contract_address: &Bech32Address,
+    tx_hash: &str,
+    tx: &TransactionOnNetwork,
+) -> Vec<ClaimEvent> {
+    decode_log_events(
+        contract_address,
+        tx_hash,
+        tx.logs.as_ref(),
+        tx.epoch,
+        tx.timestamp,
+    )
+}
+
+/// Same as `decode_transaction_events`, for logs fetched separately, such as the ones of a smart contract result.
+pub fn decode_log_events(
+    contract_address: &Bech32Address,
+    tx_hash: &str,
+    logs: Option<&ApiLogs>,
+    tx_epoch: u64,
+    timestamp: u64,
+) -> Vec<ClaimEvent> {
+    let Some(logs) = logs else {
+        return Vec::new();
+    };
+
+    logs.events
+        .iter()
+        .filter(|event| event.address.to_bytes() == *contract_address.as_address().as_array())
+        .filter_map(
+            |event| match decode_event(event, tx_hash, tx_epoch, timestamp) {
+                Ok(decoded) => decoded,
+                Err(err) => {
+                    println!("Skipping {} event of tx {tx_hash}: {err}", event.identifier);
+                    None
+                }
+            },
+        )
+        .collect()
+}
+
+/// Decodes a single event, `None` if it is not a claim event.
+pub fn decode_event(
+    event: &Events,
+    tx_hash: &str,
+    tx_epoch: u64,
+    timestamp: u64,
+) -> Result<Option<ClaimEvent>, String> {
+    let Some(topics) = &event.topics else {
+        return Ok(None);
+    };
+    let topics = topics
+        .iter()
+        .map(|topic| STANDARD.decode(topic))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| format!("invalid topic base64, {err}"))?;
+    let Some(kind) = topics
+        .first()
+        .and_then(|identifier| ClaimEventKind::from_identifier(identifier))
+    else {
+        return Ok(None);
+    };
+    let data = STANDARD
+        .decode(event.data.as_deref().unwrap_or_default())
+        .map_err(|err| format!("invalid data base64, {err}"))?;
+
+    let mut decoded = ClaimEvent {
+        tx_hash: tx_hash.to_string(),
+        epoch: tx_epoch,
+        timestamp,
+        kind,
+        address: None,
+        address_info: None,
+        previous_address_info: None,
+        missed_epochs: None,
+        payment: None,
+        delegate: None,
+    };
+
+    if kind == ClaimEventKind::SetRepairPayment {
+        let [_, epoch, token_identifier, token_nonce, amount] = topics.as_slice() else {
+            return Err(format!("unexpected number of topics: {}", topics.len()));
+        };
+        decoded.epoch = decode_u64(epoch)?;
+        decoded.payment = Some(PaymentRecord {
+            token_identifier: String::from_utf8(token_identifier.clone())
+                .map_err(|_| "invalid token identifier".to_string())?,
+            token_nonce: decode_u64(token_nonce)?,
+            amount: BigUint::<StaticApi>::top_decode(amount.clone())
+                .map_err(|err| format!("invalid amount, {err:?}"))?
+                .to_display()
+                .to_string(),
+        });
+        return Ok(Some(decoded));
+    }
+
+    decoded.address = Some(decode_address(
+        topics.get(1).ok_or("missing address topic")?,
+    )?);
+    if topics.len() == 2 {
+        let address_info = AddressInfo::top_decode(data)
+            .map_err(|err| format!("invalid address info, {err:?}"))?;
+        decoded.address_info = Some(AddressInfoRecord::from(address_info));
+        return Ok(Some(decoded));
+    }
+
+    decoded.epoch = decode_u64(&topics[2])?;
+    if kind == ClaimEventKind::ClaimAndRepair {
+        decoded.missed_epochs = topics.get(3).map(|bytes| decode_u64(bytes)).transpose()?;
+        decoded.payment = topics
+            .get(4)
+            .map(|bytes| decode_payment(bytes))
+            .transpose()?;
+    }
+    if matches!(
+        kind,
+        ClaimEventKind::DelegatedClaim | ClaimEventKind::SignedClaim
+    ) {
+        decoded.delegate = topics
+            .get(3)
+            .map(|bytes| decode_address(bytes))
+            .transpose()?;
+    }
+
+    let update = AddressInfoUpdate::top_decode(data)
+        .map_err(|err| format!("invalid address info update, {err:?}"))?;
+    decoded.previous_address_info = Some(AddressInfoRecord::from(update.previous));
+    decoded.address_info = Some(AddressInfoRecord::from(update.current));
+
+    Ok(Some(decoded))
+}
+
+fn decode_u64(bytes: &[u8]) -> Result<u64, String> {
+    u64::top_decode(bytes).map_err(|err| format!("invalid u64 topic, {err:?}"))
+}
+
+fn decode_address(bytes: &[u8]) -> Result<Bech32Address, String> {
+    if bytes.len() != 32 {
+        return Err(format!("invalid address topic of {} bytes", bytes.len()));
+    }
+
+    Ok(Bech32Address::from(Address::from_slice(bytes)))
+}
+
+fn decode_payment(bytes: &[u8]) -> Result<PaymentRecord, String> {
+    let payment = EgldOrEsdtTokenPayment::<StaticApi>::top_decode(bytes)
+        .map_err(|err| format!("invalid payment topic, {err:?}"))?;
+
+    Ok(PaymentRecord {
+        token_identifier: payment.token_identifier.into_name().to_string(),
+        token_nonce: payment.token_nonce,
+        amount: payment.amount.to_display().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use multiversx_sc::codec::top_encode_to_vec_u8_or_panic;
+    use multiversx_sc_snippets::sdk::data::address::Address as SdkAddress;
+
+    const TX_EPOCH: u64 = 7;
+    const TIMESTAMP: u64 = 1_700_000_000;
+
+    fn event(topics: &[Vec<u8>], data: Vec<u8>) -> Events {
+        Events {
+            address: SdkAddress::from_bytes([0; 32]),
+            identifier: "claim".to_string(),
+            topics: Some(topics.iter().map(|topic| STANDARD.encode(topic)).collect()),
+            data: Some(STANDARD.encode(data)),
+        }
+    }
+
+    fn decode(event: &Events) -> Result<Option<ClaimEvent>, String> {
+        decode_event(event, "hash", TX_EPOCH, TIMESTAMP)
+    }
+
+    fn address(byte: u8) -> Vec<u8> {
+        vec![byte; 32]
+    }
+
+    fn update() -> AddressInfoUpdate {
+        AddressInfoUpdate::new(AddressInfo::new(1, 4, 1, 1), AddressInfo::new(2, 5, 2, 2))
+    }
+
+    fn assert_update(decoded: &ClaimEvent) {
+        assert_eq!(
+            decoded.previous_address_info,
+            Some(AddressInfoRecord::from(AddressInfo::new(1, 4, 1, 1)))
+        );
+        assert_eq!(
+            decoded.address_info,
+            Some(AddressInfoRecord::from(AddressInfo::new(2, 5, 2, 2)))
+        );
+    }
+
+    #[test]
+    fn decode_legacy_claim() {
+        let address_info = AddressInfo::new(3, 6, 3, 3);
+        let event = event(
+            &[b"new_claim".to_vec(), address(1)],
+            top_encode_to_vec_u8_or_panic(&address_info),
+        );
+
+        let decoded = decode(&event).unwrap().unwrap();
+        assert_eq!(decoded.kind, ClaimEventKind::Claim);
+        assert_eq!(decoded.epoch, TX_EPOCH);
+        assert_eq!(
+            decoded.address,
+            Some(Bech32Address::from(Address::from([1; 32])))
+        );
+        assert_eq!(
+            decoded.address_info,
+            Some(AddressInfoRecord::from(address_info))
+        );
+        assert_eq!(decoded.previous_address_info, None);
+    }
+
+    #[test]
+    fn decode_claim() {
+        let event = event(
+            &[
+                b"new_claim".to_vec(),
+                address(1),
+                top_encode_to_vec_u8_or_panic(&5u64),
+            ],
+            top_encode_to_vec_u8_or_panic(&update()),
+        );
+
+        let decoded = decode(&event).unwrap().unwrap();
+        assert_eq!(decoded.kind, ClaimEventKind::Claim);
+        assert_eq!(decoded.epoch, 5);
+        assert_eq!(decoded.delegate, None);
+        assert_update(&decoded);
+    }
+
+    #[test]
+    fn decode_delegated_and_signed_claims() {
+        let delegated = event(
+            &[
+                b"new_delegated_claim".to_vec(),
+                address(1),
+                top_encode_to_vec_u8_or_panic(&5u64),
+                address(2),
+            ],
+            top_encode_to_vec_u8_or_panic(&update()),
+        );
+        let signed = event(
+            &[
+                b"new_signed_claim".to_vec(),
+                address(1),
+                top_encode_to_vec_u8_or_panic(&5u64),
+                address(2),
+                top_encode_to_vec_u8_or_panic(&9u64),
+            ],
+            top_encode_to_vec_u8_or_panic(&update()),
+        );
+
+        for (event, kind) in [
+            (delegated, ClaimEventKind::DelegatedClaim),
+            (signed, ClaimEventKind::SignedClaim),
+        ] {
+            let decoded = decode(&event).unwrap().unwrap();
+            assert_eq!(decoded.kind, kind);
+            assert_eq!(decoded.epoch, 5);
+            assert_eq!(
+                decoded.delegate,
+                Some(Bech32Address::from(Address::from([2; 32])))
+            );
+            assert_update(&decoded);
+        }
+    }
+
+    #[test]
+    fn decode_claim_and_repair() {
+        let payment = EgldOrEsdtTokenPayment::<StaticApi>::new(
+            EgldOrEsdtTokenIdentifier::esdt("VLAD-6bde05"),
+            0,
+            BigUint::from(1u64),
+        );
+        let event = event(
+            &[
+                b"new_claim_and_repair".to_vec(),
+                address(1),
+                top_encode_to_vec_u8_or_panic(&5u64),
+                top_encode_to_vec_u8_or_panic(&2u64),
+                top_encode_to_vec_u8_or_panic(&payment),
+            ],
+            top_encode_to_vec_u8_or_panic(&update()),
+        );
+
+        let decoded = decode(&event).unwrap().unwrap();
+        assert_eq!(decoded.kind, ClaimEventKind::ClaimAndRepair);
+        assert_eq!(decoded.epoch, 5);
+        assert_eq!(decoded.missed_epochs, Some(2));
+        assert_eq!(
+            decoded.payment,
+            Some(PaymentRecord {
+                token_identifier: "VLAD-6bde05".to_string(),
+                token_nonce: 0,
+                amount: "1".to_string(),
+            })
+        );
+        assert_update(&decoded);
+    }
+
+    #[test]
+    fn decode_set_repair_payment() {
+        let event = event(
+            &[
+                b"new_set_repair_payment".to_vec(),
+                top_encode_to_vec_u8_or_panic(&5u64),
+                b"EGLD".to_vec(),
+                Vec::new(),
+                top_encode_to_vec_u8_or_panic(&BigUint::<StaticApi>::from(10u64)),
+            ],
+            Vec::new(),
+        );
+
+        let decoded = decode(&event).unwrap().unwrap();
+        assert_eq!(decoded.kind, ClaimEventKind::SetRepairPayment);
+        assert_eq!(decoded.epoch, 5);
+        assert_eq!(decoded.address, None);
+        assert_eq!(
+            decoded.payment,
+            Some(PaymentRecord {
+                token_identifier: "EGLD".to_string(),
+                token_nonce: 0,
+                amount: "10".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn skip_unknown_events() {
+        let event = event(&[b"admin_action".to_vec(), address(1)], Vec::new());
+
+        assert_eq!(decode(&event), Ok(None));
+    }
+
+    #[test]
+    fn reject_malformed_events() {
+        let bad_address = event(
+            &[b"new_claim".to_vec(), vec![1; 3]],
+            top_encode_to_vec_u8_or_panic(&AddressInfo::new(3, 6, 3, 3)),
+        );
+        let bad_data = event(
+            &[
+                b"new_claim".to_vec(),
+                address(1),
+                top_encode_to_vec_u8_or_panic(&5u64),
+            ],
+            vec![1, 2, 3],
+        );
+        let bad_topics = Events {
+            topics: Some(vec!["not base64!".to_string()]),
+            ..bad_data.clone()
+        };
+
+        assert!(decode(&bad_address).is_err());
+        assert!(decode(&bad_data).is_err());
+        assert!(decode(&bad_topics).is_err());
+    }
+}
@@ -0,0 +1,293 @@
+use multiversx_sc_snippets::{
+    imports::*,
+    multiversx_sc::tuple_util::NestedTupleFlatten,
+    multiversx_sc_scenario::{
+        scenario::tx_to_step::{StepWithResponse, TxToStep},
+        scenario_model::{TxResponse, U64Value},
+    },
+    sdk::{
+        data::{
+            address::Address as ErdrsAddress, network_config::NetworkConfig,
+            transaction::Transaction,
+        },
+        utils::base64_encode,
+    },
+    InteractorEnvExec,
+};
+use std::fmt;
+
+const DEFAULT_GAS_MARGIN_PERCENT: u64 = 10;
+const DRY_RUN_FLAG: &str = "--dry-run";
+const GAS_MARGIN_FLAG: &str = "--gas-margin";
+const DEPLOY_RECEIVER: [u8; 32] = [0u8; 32];
+const EGLD_DECIMALS: i32 = 18;
+
+/// How the gas limit of every transaction is derived from its simulation.
+#[derive(Debug, Clone, Copy)]
+pub struct GasSettings {
+    pub margin_percent: u64,
+    pub dry_run: bool,
+}
+
+impl Default for GasSettings {
+    fn default() -> Self {
+        GasSettings {
+            margin_percent: DEFAULT_GAS_MARGIN_PERCENT,
+            dry_run: false,
+        }
+    }
+}
+
+impl GasSettings {
+    /// Takes `--dry-run` and `--gas-margin <percent>` out of the command line arguments, returning the others.
+    pub fn from_args(mut args: impl Iterator<Item = String>) -> (Self, Vec<String>) {
+        let mut settings = GasSettings::default();
+        let mut remaining = Vec::new();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                DRY_RUN_FLAG => settings.dry_run = true,
+                GAS_MARGIN_FLAG => {
+                    settings.margin_percent = args
+                        .next()
+                        .and_then(|margin| margin.parse().ok())
+                        .expect("--gas-margin expects a percentage")
+                }
+                _ => remaining.push(arg),
+            }
+        }
+
+        (settings, remaining)
+    }
+
+    pub fn gas_limit(&self, gas_used: u64) -> u64 {
+        gas_used + gas_used * self.margin_percent / 100
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct GasEstimate {
+    pub gas_used: u64,
+    pub gas_limit: u64,
+    /// Fee for the simulated gas usage, in the smallest EGLD denomination.
+    pub fee: u128,
+}
+
+impl fmt::Display for GasEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "simulated gas: {}, gas limit: {}, expected fee: {} EGLD",
+            self.gas_used,
+            self.gas_limit,
+            self.fee as f64 / 10f64.powi(EGLD_DECIMALS)
+        )
+    }
+}
+
+/// Simulates transactions against the gateway before sending them with a gas limit close to their usage.
+#[derive(Debug, Clone, Copy)]
+pub struct GasEstimator {
+    pub settings: GasSettings,
+    /// Share of the gas price paid for the gas used by contract execution, as opposed to the data.
+    pub gas_price_modifier: f64,
+}
+
+impl GasEstimator {
+    pub async fn new(gateway: &str, settings: GasSettings) -> Self {
+        let url = format!("{gateway}/network/config");
+        let response: serde_json::Value = reqwest::get(url)
+            .await
+            .expect("failed to fetch network config")
+            .json()
+            .await
+            .expect("invalid network config response");
+
+        let gas_price_modifier = response["data"]["config"]["erd_gas_price_modifier"]
+            .as_str()
+            .and_then(|modifier| modifier.parse().ok())
+            .expect("missing gas price modifier in network config");
+
+        GasEstimator {
+            settings,
+            gas_price_modifier,
+        }
+    }
+
+    /// Simulates the transaction and prints the estimate, then sends it with the estimated gas limit,
+    /// unless in dry run mode, when `None` is returned.
+    pub async fn run<'w, T, RH>(
+        &self,
+        tx: T,
+    ) -> Option<<RH::ListReturns as NestedTupleFlatten>::Unpacked>
+    where
+        T: TxToStep<InteractorEnvExec<'w>, RH>,
+        T::Step: SimulatedStep + StepWithResponse,
+        RH: RHListExec<TxResponse, InteractorEnvExec<'w>>,
+        RH::ListReturns: NestedTupleFlatten,
+    {
+        let mut step_wrapper = tx.tx_to_step();
+        let estimate = self
+            .estimate_step(step_wrapper.env.world, &step_wrapper.step)
+            .await;
+        println!("{} {estimate}", step_wrapper.step.name());
+        if self.settings.dry_run {
+            return None;
+        }
+
+        step_wrapper.step.set_gas_limit(estimate.gas_limit);
+        step_wrapper.step.send(step_wrapper.env.world).await;
+        Some(step_wrapper.process_result())
+    }
+
+    /// Simulates the transaction without sending it.
+    pub async fn estimate<'w, T, RH>(&self, tx: T) -> GasEstimate
+    where
+        T: TxToStep<InteractorEnvExec<'w>, RH>,
+        T::Step: SimulatedStep,
+    {
+        let step_wrapper = tx.tx_to_step();
+        self.estimate_step(step_wrapper.env.world, &step_wrapper.step)
+            .await
+    }
+
+    async fn estimate_step<S: SimulatedStep>(
+        &self,
+        interactor: &Interactor,
+        step: &S,
+    ) -> GasEstimate {
+        let (mut transaction, data_len) = step.to_transaction(interactor);
+        transaction.nonce = interactor.recall_nonce(&step.sender()).await;
+
+        let cost = interactor
+            .proxy
+            .request_transaction_cost(&transaction)
+            .await
+            .expect("failed to simulate transaction");
+        assert!(
+            cost.return_message.is_empty(),
+            "{} would fail: {}",
+            step.name(),
+            cost.return_message
+        );
+
+        GasEstimate {
+            gas_used: cost.tx_gas_units,
+            gas_limit: self.settings.gas_limit(cost.tx_gas_units),
+            fee: self.fee(&interactor.network_config, cost.tx_gas_units, data_len),
+        }
+    }
+
+    /// The data is paid at the full gas price, the execution at the reduced one.
+    fn fee(&self, network_config: &NetworkConfig, gas_used: u64, data_len: usize) -> u128 {
+        let data_gas =
+            network_config.min_gas_limit + network_config.gas_per_data_byte * data_len as u64;
+        let execution_gas = gas_used.saturating_sub(data_gas);
+        let gas_price = network_config.min_gas_price as u128;
+
+        data_gas.min(gas_used) as u128 * gas_price
+            + (execution_gas as f64 * gas_price as f64 * self.gas_price_modifier) as u128
+    }
+}
+
+/// A scenario step that can be simulated by the gateway and sent through the interactor.
+pub trait SimulatedStep {
+    fn name(&self) -> String;
+
+    fn sender(&self) -> Address;
+
+    /// The unsigned transaction, with the length of its data field before encoding.
+    fn to_transaction(&self, interactor: &Interactor) -> (Transaction, usize);
+
+    fn set_gas_limit(&mut self, gas_limit: u64);
+
+    async fn send(&mut self, interactor: &mut Interactor);
+}
+
+impl SimulatedStep for ScCallStep {
+    fn name(&self) -> String {
+        self.tx.function.clone()
+    }
+
+    fn sender(&self) -> Address {
+        self.tx.from.to_address()
+    }
+
+    #[allow(deprecated)]
+    fn to_transaction(&self, interactor: &Interactor) -> (Transaction, usize) {
+        let contract_call = self.tx.to_contract_call();
+        let mut data = String::from_utf8(
+            contract_call
+                .basic
+                .function_call
+                .function_name
+                .to_boxed_bytes()
+                .into_vec(),
+        )
+        .unwrap();
+        for argument in contract_call.basic.function_call.arg_buffer.raw_arg_iter() {
+            data.push('@');
+            data.push_str(&hex::encode(argument.to_boxed_bytes().as_slice()));
+        }
+
+        let transaction = Transaction {
+            nonce: 0,
+            value: contract_call.egld_payment.to_alloc().to_string(),
+            sender: ErdrsAddress::from_bytes(*self.sender().as_array()),
+            receiver: ErdrsAddress::from_bytes(*contract_call.basic.to.to_address().as_array()),
+            gas_price: interactor.network_config.min_gas_price,
+            gas_limit: self.tx.gas_limit.value,
+            data: Some(base64_encode(&data)),
+            signature: None,
+            chain_id: interactor.network_config.chain_id.clone(),
+            version: interactor.network_config.min_transaction_version,
+            options: 0,
+        };
+        (transaction, data.len())
+    }
+
+    fn set_gas_limit(&mut self, gas_limit: u64) {
+        self.tx.gas_limit = U64Value::from(gas_limit);
+    }
+
+    async fn send(&mut self, interactor: &mut Interactor) {
+        interactor.sc_call(self).await;
+    }
+}
+
+impl SimulatedStep for ScDeployStep {
+    fn name(&self) -> String {
+        "deploy".to_string()
+    }
+
+    fn sender(&self) -> Address {
+        self.tx.from.to_address()
+    }
+
+    fn to_transaction(&self, interactor: &Interactor) -> (Transaction, usize) {
+        let data = self.tx.to_tx_data();
+
+        let transaction = Transaction {
+            nonce: 0,
+            value: self.tx.egld_value.value.to_string(),
+            sender: ErdrsAddress::from_bytes(*self.sender().as_array()),
+            receiver: ErdrsAddress::from_bytes(DEPLOY_RECEIVER),
+            gas_price: interactor.network_config.min_gas_price,
+            gas_limit: self.tx.gas_limit.value,
+            data: Some(base64_encode(&data)),
+            signature: None,
+            chain_id: interactor.network_config.chain_id.clone(),
+            version: interactor.network_config.min_transaction_version,
+            options: 0,
+        };
+        (transaction, data.len())
+    }
+
+    fn set_gas_limit(&mut self, gas_limit: u64) {
+        self.tx.gas_limit = U64Value::from(gas_limit);
+    }
+
+    async fn send(&mut self, interactor: &mut Interactor) {
+        interactor.sc_deploy(self).await;
+    }
+}
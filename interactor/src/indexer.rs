@@ -0,0 +1,320 @@
+use crate::claim_events::ClaimEvent;
+use multiversx_sc_snippets::imports::*;
+use multiversx_sc_snippets::sdk::data::transaction::ApiLogs;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, HashSet},
+    fs::OpenOptions,
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+};
+
+pub const API_PAGE_SIZE: usize = 50;
+
+/// Local JSON-lines database of decoded contract events, one event per line, in chain order.
+pub struct EventStore {
+    path: PathBuf,
+    events: Vec<ClaimEvent>,
+}
+
+impl EventStore {
+    pub fn open<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let mut events = Vec::new();
+        if path.exists() {
+            let file = std::fs::File::open(&path).expect("failed to open event store");
+            for line in BufReader::new(file).lines() {
+                let line = line.unwrap();
+                if line.trim().is_empty() {
+                    continue;
+                }
+                events.push(serde_json::from_str(&line).expect("invalid event store entry"));
+            }
+        }
+
+        EventStore { path, events }
+    }
+
+    pub fn events(&self) -> &[ClaimEvent] {
+        &self.events
+    }
+
+    pub fn indexed_tx_hashes(&self) -> HashSet<String> {
+        self.events
+            .iter()
+            .map(|event| event.tx_hash.clone())
+            .collect()
+    }
+
+    pub fn append(&mut self, new_events: Vec<ClaimEvent>) {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .expect("failed to open event store");
+        for event in &new_events {
+            writeln!(file, "{}", serde_json::to_string(event).unwrap()).unwrap();
+        }
+
+        self.events.extend(new_events);
+    }
+
+    /// All the events concerning an address, in chain order.
+    pub fn address_history(&self, address: &Bech32Address) -> Vec<&ClaimEvent> {
+        self.events
+            .iter()
+            .filter(|event| event.address.as_ref() == Some(address))
+            .collect()
+    }
+}
+
+/// Position of the indexer in the contract history, stored next to the event store.
+///
+/// Transactions and results without events of the contract leave no trace in the event store,
+/// so the ones already processed are tracked here instead of being fetched again on every run.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct IndexCursor {
+    /// Timestamp of the last processed transaction or result.
+    pub timestamp: u64,
+    /// The processed transactions and results with that timestamp, since a block can hold several of them.
+    pub hashes: BTreeSet<String>,
+}
+
+impl IndexCursor {
+    pub fn path(event_store_path: &str) -> String {
+        format!("{event_store_path}.cursor")
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref();
+        if !path.exists() {
+            return IndexCursor::default();
+        }
+
+        let content = std::fs::read_to_string(path).expect("failed to read index cursor");
+        serde_json::from_str(&content).expect("invalid index cursor")
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) {
+        let content = serde_json::to_string_pretty(self).unwrap();
+        std::fs::write(path, content).expect("failed to write index cursor");
+    }
+
+    pub fn is_processed(&self, source: &LogSource) -> bool {
+        source.timestamp < self.timestamp
+            || (source.timestamp == self.timestamp && self.hashes.contains(&source.hash))
+    }
+
+    /// Marks a source as processed, sources being processed oldest first.
+    pub fn advance(&mut self, source: &LogSource) {
+        if source.timestamp > self.timestamp {
+            self.timestamp = source.timestamp;
+            self.hashes.clear();
+        }
+        self.hashes.insert(source.hash.clone());
+    }
+}
+
+/// A transaction or smart contract result whose logs can hold events of the contract.
+///
+/// Events emitted while the contract is called by another contract, such as an allowed contract
+/// or a wallet contract, are in the logs of the smart contract result executed by the contract.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogSource {
+    pub kind: LogSourceKind,
+    pub hash: String,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogSourceKind {
+    Transaction,
+    Result,
+}
+
+/// An entry of the API listings, which are paginated by timestamp.
+trait ApiListItem: DeserializeOwned {
+    fn hash(&self) -> &str;
+    fn timestamp(&self) -> u64;
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiTransaction {
+    tx_hash: String,
+    timestamp: u64,
+}
+
+impl ApiListItem for ApiTransaction {
+    fn hash(&self) -> &str {
+        &self.tx_hash
+    }
+
+    fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+}
+
+#[derive(Deserialize)]
+struct ApiResultHash {
+    hash: String,
+    timestamp: u64,
+}
+
+impl ApiListItem for ApiResultHash {
+    fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+}
+
+/// A smart contract result with its logs, as returned by the MultiversX API.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiResult {
+    pub original_tx_hash: String,
+    pub timestamp: u64,
+    pub logs: Option<ApiLogs>,
+}
+
+/// Lists the transactions and smart contract results involving the contract from the `after` timestamp
+/// included, oldest first, using the MultiversX API. A transaction comes before its results.
+pub async fn fetch_contract_log_sources(
+    api_url: &str,
+    contract_address: &Bech32Address,
+    after: u64,
+) -> Vec<LogSource> {
+    let client = reqwest::Client::new();
+    let transactions: Vec<ApiTransaction> = fetch_all_pages(
+        &client,
+        &format!(
+            "{api_url}/accounts/{contract_address}/transactions?order=asc&fields=txHash,timestamp"
+        ),
+        after,
+    )
+    .await;
+    let results: Vec<ApiResultHash> = fetch_all_pages(
+        &client,
+        &format!("{api_url}/accounts/{contract_address}/results?order=asc&fields=hash,timestamp"),
+        after,
+    )
+    .await;
+
+    let mut sources: Vec<LogSource> = transactions
+        .iter()
+        .map(|tx| log_source(LogSourceKind::Transaction, tx))
+        .chain(
+            results
+                .iter()
+                .map(|result| log_source(LogSourceKind::Result, result)),
+        )
+        .collect();
+    // Stable, so that transactions stay before the results with the same timestamp
+    sources.sort_by_key(|source| source.timestamp);
+
+    sources
+}
+
+fn log_source<T: ApiListItem>(kind: LogSourceKind, item: &T) -> LogSource {
+    LogSource {
+        kind,
+        hash: item.hash().to_string(),
+        timestamp: item.timestamp(),
+    }
+}
+
+pub async fn fetch_result(api_url: &str, hash: &str) -> ApiResult {
+    reqwest::get(format!("{api_url}/results/{hash}"))
+        .await
+        .expect("failed to fetch smart contract result")
+        .json()
+        .await
+        .expect("invalid smart contract result response")
+}
+
+/// Fetches an ascending listing page by page, each page starting at the timestamp where the previous one ended.
+///
+/// Unlike offsets over the whole listing, this isn't shifted by new entries and stays under the
+/// API limit on `from + size`. Entries sharing the timestamp of a page end are fetched again and skipped,
+/// offsets only being used within a timestamp filling a whole page.
+async fn fetch_all_pages<T: ApiListItem>(
+    client: &reqwest::Client,
+    url: &str,
+    mut after: u64,
+) -> Vec<T> {
+    let mut items = Vec::new();
+    let mut fetched_hashes = HashSet::new();
+    let mut from = 0;
+    loop {
+        let page_url = format!("{url}&after={after}&from={from}&size={API_PAGE_SIZE}");
+        let page: Vec<T> = client
+            .get(page_url)
+            .send()
+            .await
+            .expect("failed to fetch contract history")
+            .json()
+            .await
+            .expect("invalid contract history response");
+
+        let page_len = page.len();
+        let last_timestamp = page.last().map(T::timestamp);
+        for item in page {
+            if fetched_hashes.insert(item.hash().to_string()) {
+                items.push(item);
+            }
+        }
+        let Some(last_timestamp) = last_timestamp.filter(|_| page_len == API_PAGE_SIZE) else {
+            return items;
+        };
+
+        if last_timestamp == after {
+            from += API_PAGE_SIZE;
+        } else {
+            after = last_timestamp;
+            from = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(hash: &str, timestamp: u64) -> LogSource {
+        LogSource {
+            kind: LogSourceKind::Transaction,
+            hash: hash.to_string(),
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn cursor_skips_the_processed_sources() {
+        let mut cursor = IndexCursor::default();
+        cursor.advance(&source("a", 10));
+        cursor.advance(&source("b", 12));
+        cursor.advance(&source("c", 12));
+
+        assert_eq!(cursor.timestamp, 12);
+        assert!(cursor.is_processed(&source("a", 10)));
+        assert!(cursor.is_processed(&source("b", 12)));
+        // a source of the same block listed after the cursor was saved
+        assert!(!cursor.is_processed(&source("d", 12)));
+        assert!(!cursor.is_processed(&source("e", 13)));
+    }
+
+    #[test]
+    fn cursor_round_trips_through_its_file() {
+        let path = std::env::temp_dir().join("on-chain-claim-index-cursor-test.cursor");
+        let mut cursor = IndexCursor::default();
+        cursor.advance(&source("a", 10));
+        cursor.save(&path);
+
+        assert_eq!(IndexCursor::load(&path), cursor);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(IndexCursor::load(&path), IndexCursor::default());
+    }
+}
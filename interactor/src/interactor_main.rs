@@ -1,41 +1,177 @@
 #![allow(non_snake_case)]
 
+mod claim_events;
+mod gas;
+mod indexer;
 mod proxy;
+mod replay;
+mod snapshot;
 
+use gas::{GasEstimator, GasSettings};
+use indexer::{EventStore, IndexCursor, LogSourceKind};
 use multiversx_sc_snippets::imports::*;
 use multiversx_sc_snippets::sdk;
 use multiversx_sc_snippets::sdk::wallet::Wallet;
+use on_chain_claim::{
+    claim_bitmap::EPOCHS_PER_WORD, referral::MAX_REFEREES_PAGE_SIZE, AddressInfo,
+};
+use replay::Replay;
 use serde::{Deserialize, Serialize};
+use snapshot::{Snapshot, SnapshotEntry, ADDRESS_INFO_STORAGE_KEY};
 use std::{
     io::{Read, Write},
     path::Path,
 };
 
 const GATEWAY: &str = sdk::gateway::DEVNET_GATEWAY;
+const API: &str = "https://devnet-api.multiversx.com";
 const STATE_FILE: &str = "state.toml";
+const SNAPSHOT_FILE: &str = "snapshot.json";
+const SNAPSHOT_IMPORT_BATCH_SIZE: usize = 50;
+const EVENT_STORE_FILE: &str = "events.jsonl";
+const METACHAIN_SHARD_ID: u32 = u32::MAX;
+/// 0.05 EGLD, the cost of issuing a token.
+const BADGE_ISSUE_COST: u64 = 50_000_000_000_000_000;
 
 #[tokio::main]
 async fn main() {
     env_logger::init();
 
-    let mut args = std::env::args();
-    let _ = args.next();
+    let (gas_settings, args) = GasSettings::from_args(std::env::args().skip(1));
+    let mut args = args.into_iter();
     let cmd = args.next().expect("at least one argument required");
-    let mut interact = ContractInteract::new().await;
+    let mut interact = ContractInteract::new(gas_settings).await;
     match cmd.as_str() {
         "deploy" => interact.deploy().await,
         // "upgrade" => interact.upgrade().await,
         "claim" => interact.claim().await,
+        "claimWithReferrer" => {
+            let referrer = args.next().expect("referrer argument required");
+            interact.claim_with_referrer(&referrer).await
+        }
         "claimAndRepair" => interact.claim_and_repair().await,
         "updateState" => interact.update_state().await,
-        "setRepairStreakPayment" => interact.set_repair_streak_payment().await,
         "getAddressInfo" => interact.get_address_info().await,
         "canBeRepaired" => interact.can_be_repaired().await,
         "getRepairStreakPayment" => interact.repair_streak_payment().await,
-        "isAdmin" => interact.is_admin().await,
-        "addAdmin" => interact.add_admin().await,
-        "removeAdmin" => interact.remove_admin().await,
-        "getAdmins" => interact.admins().await,
+        "hasRole" => interact.has_role().await,
+        "getRoleHolders" => interact.role_holders().await,
+        "pause" => interact.pause().await,
+        "unpause" => interact.unpause().await,
+        "isPaused" => interact.paused().await,
+        "getAdminActionCount" => interact.admin_action_count().await,
+        "proposeConfigChange" => interact.propose_config_change().await,
+        "proposeRepairPayment" => {
+            let token = args.next().expect("token argument required");
+            let amount = args.next().expect("amount argument required");
+            interact
+                .propose_repair_payment(&token, amount.parse().expect("invalid amount"))
+                .await
+        }
+        "executeConfigChange" => {
+            let id = args.next().expect("proposal id argument required");
+            interact
+                .execute_config_change(id.parse().expect("invalid proposal id"))
+                .await
+        }
+        "cancelConfigChange" => {
+            let id = args.next().expect("proposal id argument required");
+            interact
+                .cancel_config_change(id.parse().expect("invalid proposal id"))
+                .await
+        }
+        "getPendingConfigChanges" => interact.pending_config_changes().await,
+        "getTimelockEpochs" => interact.timelock_epochs().await,
+        "setStateOverrideQuorum" => interact.set_state_override_quorum().await,
+        "approveStateOverride" => {
+            let id = args.next().expect("proposal id argument required");
+            interact
+                .approve_state_override(id.parse().expect("invalid proposal id"))
+                .await
+        }
+        "cancelStateOverride" => {
+            let id = args.next().expect("proposal id argument required");
+            interact
+                .cancel_state_override(id.parse().expect("invalid proposal id"))
+                .await
+        }
+        "getPendingStateOverrides" => interact.pending_state_overrides().await,
+        "getStateOverrideQuorum" => {
+            println!("Result: {}", interact.fetch_state_override_quorum().await)
+        }
+        "setClaimHistorySize" => interact.set_claim_history_size().await,
+        "getClaimHistory" => {
+            let address = args.next().expect("address argument required");
+            interact.claim_history(&address).await
+        }
+        "getClaimBitmap" => {
+            let address = args.next().expect("address argument required");
+            let start_epoch = args.next().expect("start epoch argument required");
+            let end_epoch = args.next().expect("end epoch argument required");
+            interact
+                .claim_bitmap(
+                    &address,
+                    start_epoch.parse().expect("invalid start epoch"),
+                    end_epoch.parse().expect("invalid end epoch"),
+                )
+                .await
+        }
+        "issueBadgeCollection" => interact.issue_badge_collection().await,
+        "setBadgeTiers" => interact.set_badge_tiers().await,
+        "getBadge" => {
+            let address = args.next().expect("address argument required");
+            interact.badge(&address).await
+        }
+        "hasMinStreak" => {
+            let address = args.next().expect("address argument required");
+            let min_streak = args.next().expect("min streak argument required");
+            interact
+                .has_min_streak(&address, min_streak.parse().expect("invalid min streak"))
+                .await
+        }
+        "hasClaimedInLastEpochs" => {
+            let address = args.next().expect("address argument required");
+            let epochs = args.next().expect("epochs argument required");
+            interact
+                .has_claimed_in_last_epochs(&address, epochs.parse().expect("invalid epochs"))
+                .await
+        }
+        "isActiveClaimer" => {
+            let address = args.next().expect("address argument required");
+            interact.is_active_claimer(&address).await
+        }
+        "setReferralMilestones" => interact.set_referral_milestones().await,
+        "getReferrals" => {
+            let address = args.next().expect("address argument required");
+            interact.referrals(&address).await
+        }
+        "getRepairPaymentSink" => interact.repair_payment_sink().await,
+        "getMaxRepairGap" => println!("Result: {}", interact.fetch_max_repair_gap().await),
+        "exportSnapshot" => {
+            let path = args.next().unwrap_or_else(|| SNAPSHOT_FILE.to_string());
+            interact.export_snapshot(&path).await
+        }
+        "importSnapshot" => {
+            let path = args.next().unwrap_or_else(|| SNAPSHOT_FILE.to_string());
+            interact.import_snapshot(&path).await
+        }
+        "indexEvents" => {
+            let path = args.next().unwrap_or_else(|| EVENT_STORE_FILE.to_string());
+            interact.index_events(&path).await
+        }
+        "getAddressHistory" => {
+            let address = args.next().expect("address argument required");
+            let path = args.next().unwrap_or_else(|| EVENT_STORE_FILE.to_string());
+            interact.address_history(&address, &path)
+        }
+        "replayEvents" => {
+            let path = args.next().unwrap_or_else(|| EVENT_STORE_FILE.to_string());
+            interact.replay_events(&path).await
+        }
+        "previewClaim" => {
+            let address = args.next().expect("address argument required");
+            interact.preview_claim(&address).await
+        }
         _ => panic!("unknown command: {}", &cmd),
     }
 }
@@ -85,10 +221,11 @@ struct ContractInteract {
     wallet_address: Address,
     contract_code: BytesValue,
     state: State,
+    gas: GasEstimator,
 }
 
 impl ContractInteract {
-    async fn new() -> Self {
+    async fn new(gas_settings: GasSettings) -> Self {
         let mut interactor = Interactor::new(GATEWAY).await;
         let wallet_address =
             interactor.register_wallet(Wallet::from_pem_file("wallet.pem").expect("msg"));
@@ -103,6 +240,7 @@ impl ContractInteract {
             wallet_address,
             contract_code,
             state: State::load_state(),
+            gas: GasEstimator::new(GATEWAY, gas_settings).await,
         }
     }
 
@@ -112,18 +250,21 @@ impl ContractInteract {
             TokenIdentifier::from_esdt_bytes(token_identifier_str.as_bytes());
         let repair_streak_token_nonce = 0u64;
 
-        let new_address = self
-            .interactor
-            .tx()
-            .from(&self.wallet_address)
-            .gas(NumExpr("30,000,000"))
-            .typed(proxy::OnChainClaimContractProxy)
-            .init(repair_streak_token_id, repair_streak_token_nonce)
-            .code(&self.contract_code)
-            .returns(ReturnsNewAddress)
-            .prepare_async()
-            .run()
-            .await;
+        let Some(new_address) = self
+            .gas
+            .run(
+                self.interactor
+                    .tx()
+                    .from(&self.wallet_address)
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .init(repair_streak_token_id, repair_streak_token_nonce)
+                    .code(&self.contract_code)
+                    .returns(ReturnsNewAddress),
+            )
+            .await
+        else {
+            return;
+        };
         let new_address_bech32 = bech32::encode(&new_address);
         self.state.set_address(Bech32Address::from_bech32_string(
             new_address_bech32.clone(),
@@ -150,97 +291,627 @@ impl ContractInteract {
     // }
 
     async fn claim(&mut self) {
-        let response = self
+        let Some(response) = self
+            .gas
+            .run(
+                self.interactor
+                    .tx()
+                    .from(&self.wallet_address)
+                    .to(self.state.current_address())
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .claim()
+                    .returns(ReturnsResultUnmanaged),
+            )
+            .await
+        else {
+            return;
+        };
+
+        println!("Result: {response:?}");
+    }
+
+    async fn claim_with_referrer(&mut self, referrer: &str) {
+        let referrer = Bech32Address::from_bech32_string(referrer.to_string());
+
+        let Some(response) = self
+            .gas
+            .run(
+                self.interactor
+                    .tx()
+                    .from(&self.wallet_address)
+                    .to(self.state.current_address())
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .claim_with_referrer(referrer)
+                    .returns(ReturnsResultUnmanaged),
+            )
+            .await
+        else {
+            return;
+        };
+
+        println!("Result: {response:?}");
+    }
+
+    /// Pays the configured repair payment, in EGLD or ESDT.
+    async fn claim_and_repair(&mut self) {
+        let payment = self
             .interactor
-            .tx()
-            .from(&self.wallet_address)
+            .query()
             .to(self.state.current_address())
-            .gas(NumExpr("30,000,000"))
             .typed(proxy::OnChainClaimContractProxy)
-            .claim()
-            .returns(ReturnsResultUnmanaged)
+            .repair_streak_payment()
+            .returns(ReturnsResult)
             .prepare_async()
             .run()
             .await;
 
+        let Some(response) = self
+            .gas
+            .run(
+                self.interactor
+                    .tx()
+                    .from(&self.wallet_address)
+                    .to(self.state.current_address())
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .claim_and_repair()
+                    .payment(payment)
+                    .returns(ReturnsResultUnmanaged),
+            )
+            .await
+        else {
+            return;
+        };
+
         println!("Result: {response:?}");
     }
 
-    async fn claim_and_repair(&mut self) {
-        let token_id = String::new();
-        let token_nonce = 0u64;
-        let token_amount = BigUint::<StaticApi>::from(0u128);
+    async fn update_state(&mut self) {
+        let address = bech32::decode("");
+        let current_streak = 0u64;
+        let last_epoch_claimed = 0u64;
+        let total_epochs_claimed = 0u64;
+        let best_streak = 0u64;
+
+        let Some(response) = self
+            .gas
+            .run(
+                self.interactor
+                    .tx()
+                    .from(&self.wallet_address)
+                    .to(self.state.current_address())
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .update_state(
+                        address,
+                        current_streak,
+                        last_epoch_claimed,
+                        total_epochs_claimed,
+                        best_streak,
+                    )
+                    .returns(ReturnsResultUnmanaged),
+            )
+            .await
+        else {
+            return;
+        };
+
+        println!("Result: {response:?}");
+    }
+
+    async fn propose_config_change(&mut self) {
+        let change = proxy::ConfigChange::SetRepairStreakPayment {
+            token_identifier: TokenIdentifier::from_esdt_bytes(&b""[..]),
+            token_nonce: 0u64,
+        };
+
+        let Some(response) = self
+            .gas
+            .run(
+                self.interactor
+                    .tx()
+                    .from(&self.wallet_address)
+                    .to(self.state.current_address())
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .propose_config_change(change)
+                    .returns(ReturnsResultUnmanaged),
+            )
+            .await
+        else {
+            return;
+        };
+
+        println!("Proposal id: {response}");
+    }
+
+    /// Proposes `amount` of `token`, in its smallest denomination, as repair payment. `EGLD` pays in EGLD.
+    async fn propose_repair_payment(&mut self, token: &str, amount: u128) {
+        let change = proxy::ConfigChange::SetRepairPayment {
+            payment: EgldOrEsdtTokenPayment::new(
+                EgldOrEsdtTokenIdentifier::parse(ManagedBuffer::from(token)),
+                0u64,
+                BigUint::from(amount),
+            ),
+        };
+
+        let Some(response) = self
+            .gas
+            .run(
+                self.interactor
+                    .tx()
+                    .from(&self.wallet_address)
+                    .to(self.state.current_address())
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .propose_config_change(change)
+                    .returns(ReturnsResultUnmanaged),
+            )
+            .await
+        else {
+            return;
+        };
+
+        println!("Proposal id: {response}");
+    }
+
+    async fn execute_config_change(&mut self, id: u64) {
+        let Some(response) = self
+            .gas
+            .run(
+                self.interactor
+                    .tx()
+                    .from(&self.wallet_address)
+                    .to(self.state.current_address())
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .execute_config_change(id)
+                    .returns(ReturnsResultUnmanaged),
+            )
+            .await
+        else {
+            return;
+        };
+
+        println!("Result: {response:?}");
+    }
+
+    async fn cancel_config_change(&mut self, id: u64) {
+        let Some(response) = self
+            .gas
+            .run(
+                self.interactor
+                    .tx()
+                    .from(&self.wallet_address)
+                    .to(self.state.current_address())
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .cancel_config_change(id)
+                    .returns(ReturnsResultUnmanaged),
+            )
+            .await
+        else {
+            return;
+        };
+
+        println!("Result: {response:?}");
+    }
 
-        let response = self
+    async fn pending_config_changes(&mut self) {
+        let result_value = self
             .interactor
-            .tx()
-            .from(&self.wallet_address)
+            .query()
             .to(self.state.current_address())
-            .gas(NumExpr("30,000,000"))
             .typed(proxy::OnChainClaimContractProxy)
-            .claim_and_repair()
-            .payment((
-                TokenIdentifier::from(token_id.as_str()),
-                token_nonce,
-                token_amount,
-            ))
+            .get_pending_config_changes()
             .returns(ReturnsResultUnmanaged)
             .prepare_async()
             .run()
             .await;
 
+        println!("Result: {result_value:?}");
+    }
+
+    async fn timelock_epochs(&mut self) {
+        let result_value = self
+            .interactor
+            .query()
+            .to(self.state.current_address())
+            .typed(proxy::OnChainClaimContractProxy)
+            .get_timelock_epochs()
+            .returns(ReturnsResultUnmanaged)
+            .prepare_async()
+            .run()
+            .await;
+
+        println!("Result: {result_value:?}");
+    }
+
+    async fn set_state_override_quorum(&mut self) {
+        let quorum = 2u32;
+        let expiry_epochs = 5u64;
+
+        let Some(response) = self
+            .gas
+            .run(
+                self.interactor
+                    .tx()
+                    .from(&self.wallet_address)
+                    .to(self.state.current_address())
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .set_state_override_quorum(quorum, expiry_epochs)
+                    .returns(ReturnsResultUnmanaged),
+            )
+            .await
+        else {
+            return;
+        };
+
         println!("Result: {response:?}");
     }
 
-    async fn update_state(&mut self) {
-        let address = bech32::decode("");
-        let current_streak = 0u64;
-        let last_epoch_claimed = 0u64;
-        let total_epochs_claimed = 0u64;
-        let best_streak = 0u64;
+    async fn approve_state_override(&mut self, id: u64) {
+        let Some(response) = self
+            .gas
+            .run(
+                self.interactor
+                    .tx()
+                    .from(&self.wallet_address)
+                    .to(self.state.current_address())
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .approve_state_override(id)
+                    .returns(ReturnsResultUnmanaged),
+            )
+            .await
+        else {
+            return;
+        };
+
+        println!("Result: {response:?}");
+    }
+
+    async fn cancel_state_override(&mut self, id: u64) {
+        let Some(response) = self
+            .gas
+            .run(
+                self.interactor
+                    .tx()
+                    .from(&self.wallet_address)
+                    .to(self.state.current_address())
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .cancel_state_override(id)
+                    .returns(ReturnsResultUnmanaged),
+            )
+            .await
+        else {
+            return;
+        };
+
+        println!("Result: {response:?}");
+    }
 
-        let response = self
+    async fn pending_state_overrides(&mut self) {
+        let result_value = self
             .interactor
-            .tx()
-            .from(&self.wallet_address)
+            .query()
             .to(self.state.current_address())
-            .gas(NumExpr("30,000,000"))
             .typed(proxy::OnChainClaimContractProxy)
-            .update_state(
-                address,
-                current_streak,
-                last_epoch_claimed,
-                total_epochs_claimed,
-                best_streak,
+            .get_pending_state_overrides()
+            .returns(ReturnsResultUnmanaged)
+            .prepare_async()
+            .run()
+            .await;
+
+        println!("Result: {result_value:?}");
+    }
+
+    async fn fetch_state_override_quorum(&mut self) -> u32 {
+        self.interactor
+            .query()
+            .to(self.state.current_address())
+            .typed(proxy::OnChainClaimContractProxy)
+            .state_override_quorum()
+            .returns(ReturnsResultUnmanaged)
+            .prepare_async()
+            .run()
+            .await
+    }
+
+    async fn set_claim_history_size(&mut self) {
+        let size = 30u32;
+
+        let Some(response) = self
+            .gas
+            .run(
+                self.interactor
+                    .tx()
+                    .from(&self.wallet_address)
+                    .to(self.state.current_address())
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .set_claim_history_size(size)
+                    .returns(ReturnsResultUnmanaged),
             )
+            .await
+        else {
+            return;
+        };
+
+        println!("Result: {response:?}");
+    }
+
+    async fn claim_history(&mut self, address: &str) {
+        let address = Bech32Address::from_bech32_string(address.to_string());
+        let entries = self
+            .interactor
+            .query()
+            .to(self.state.current_address())
+            .typed(proxy::OnChainClaimContractProxy)
+            .get_claim_history(address)
             .returns(ReturnsResultUnmanaged)
             .prepare_async()
             .run()
             .await;
 
+        for entry in entries.into_vec() {
+            println!("epoch {}: {:?}", entry.epoch, entry.kind);
+        }
+    }
+
+    /// Prints one line per epoch of the range: `C` claimed, `R` repaired, `.` missed.
+    async fn claim_bitmap(&mut self, address: &str, start_epoch: u64, end_epoch: u64) {
+        let address = Bech32Address::from_bech32_string(address.to_string());
+        let words = self
+            .interactor
+            .query()
+            .to(self.state.current_address())
+            .typed(proxy::OnChainClaimContractProxy)
+            .get_claim_bitmap(address, start_epoch, end_epoch)
+            .returns(ReturnsResultUnmanaged)
+            .prepare_async()
+            .run()
+            .await;
+
+        for word in words.into_vec() {
+            for bit in 0..EPOCHS_PER_WORD {
+                let epoch = word.first_epoch + bit;
+                if epoch < start_epoch || epoch > end_epoch {
+                    continue;
+                }
+
+                let mark = if word.claimed >> bit & 1 == 1 {
+                    'C'
+                } else if word.repaired >> bit & 1 == 1 {
+                    'R'
+                } else {
+                    '.'
+                };
+                println!("epoch {epoch}: {mark}");
+            }
+        }
+    }
+
+    async fn issue_badge_collection(&mut self) {
+        let Some(response) = self
+            .gas
+            .run(
+                self.interactor
+                    .tx()
+                    .from(&self.wallet_address)
+                    .to(self.state.current_address())
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .issue_badge_collection("StreakBadges", "STREAK")
+                    .egld(BADGE_ISSUE_COST)
+                    .returns(ReturnsResultUnmanaged),
+            )
+            .await
+        else {
+            return;
+        };
+
         println!("Result: {response:?}");
     }
 
-    async fn set_repair_streak_payment(&mut self) {
-        let repair_streak_token_identifier = TokenIdentifier::from_esdt_bytes(&b""[..]);
-        let repair_streak_token_nonce = 0u64;
+    async fn set_badge_tiers(&mut self) {
+        let (bronze, silver, gold) = (7u64, 30u64, 100u64);
 
-        let response = self
+        let Some(response) = self
+            .gas
+            .run(
+                self.interactor
+                    .tx()
+                    .from(&self.wallet_address)
+                    .to(self.state.current_address())
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .set_badge_tiers(bronze, silver, gold)
+                    .returns(ReturnsResultUnmanaged),
+            )
+            .await
+        else {
+            return;
+        };
+
+        println!("Result: {response:?}");
+    }
+
+    async fn badge(&mut self, address: &str) {
+        let address = Bech32Address::from_bech32_string(address.to_string());
+        let nonce = self
+            .interactor
+            .query()
+            .to(self.state.current_address())
+            .typed(proxy::OnChainClaimContractProxy)
+            .badge_nonce(&address)
+            .returns(ReturnsResultUnmanaged)
+            .prepare_async()
+            .run()
+            .await;
+        if nonce == 0 {
+            println!("No badge");
+            return;
+        }
+
+        let badge = self
+            .interactor
+            .query()
+            .to(self.state.current_address())
+            .typed(proxy::OnChainClaimContractProxy)
+            .get_badge(&address)
+            .returns(ReturnsResult)
+            .prepare_async()
+            .run()
+            .await;
+        if let OptionalValue::Some(badge) = badge {
+            println!(
+                "Badge {nonce}: {:?}, best streak {} reached in epoch {}",
+                badge.tier, badge.best_streak, badge.tier_epoch
+            );
+        }
+    }
+
+    async fn has_min_streak(&mut self, address: &str, min_streak: u64) {
+        let address = Bech32Address::from_bech32_string(address.to_string());
+        let result = self
+            .interactor
+            .query()
+            .to(self.state.current_address())
+            .typed(proxy::OnChainClaimContractProxy)
+            .has_min_streak(address, min_streak)
+            .returns(ReturnsResultUnmanaged)
+            .prepare_async()
+            .run()
+            .await;
+
+        println!("Result: {result}");
+    }
+
+    async fn has_claimed_in_last_epochs(&mut self, address: &str, epochs: u64) {
+        let address = Bech32Address::from_bech32_string(address.to_string());
+        let result = self
             .interactor
-            .tx()
-            .from(&self.wallet_address)
+            .query()
             .to(self.state.current_address())
-            .gas(NumExpr("30,000,000"))
             .typed(proxy::OnChainClaimContractProxy)
-            .set_repair_streak_payment(repair_streak_token_identifier, repair_streak_token_nonce)
+            .has_claimed_in_last_epochs(address, epochs)
             .returns(ReturnsResultUnmanaged)
             .prepare_async()
             .run()
             .await;
 
+        println!("Result: {result}");
+    }
+
+    async fn is_active_claimer(&mut self, address: &str) {
+        let address = Bech32Address::from_bech32_string(address.to_string());
+        let result = self
+            .interactor
+            .query()
+            .to(self.state.current_address())
+            .typed(proxy::OnChainClaimContractProxy)
+            .is_active_claimer(address)
+            .returns(ReturnsResultUnmanaged)
+            .prepare_async()
+            .run()
+            .await;
+
+        println!("Result: {result}");
+    }
+
+    async fn set_referral_milestones(&mut self) {
+        let milestones = MultiValueVec::from(vec![
+            MultiValue2::from((7u64, 10u64)),
+            MultiValue2::from((30u64, 50u64)),
+        ]);
+
+        let Some(response) = self
+            .gas
+            .run(
+                self.interactor
+                    .tx()
+                    .from(&self.wallet_address)
+                    .to(self.state.current_address())
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .set_referral_milestones(milestones)
+                    .returns(ReturnsResultUnmanaged),
+            )
+            .await
+        else {
+            return;
+        };
+
         println!("Result: {response:?}");
     }
 
+    /// Prints the points of a referrer and all its referees, one page at a time.
+    async fn referrals(&mut self, address: &str) {
+        let address = Bech32Address::from_bech32_string(address.to_string());
+        let points = self
+            .interactor
+            .query()
+            .to(self.state.current_address())
+            .typed(proxy::OnChainClaimContractProxy)
+            .referral_points(&address)
+            .returns(ReturnsResultUnmanaged)
+            .prepare_async()
+            .run()
+            .await;
+        println!("Points: {points}");
+
+        let mut from = 0usize;
+        loop {
+            let referees = self
+                .interactor
+                .query()
+                .to(self.state.current_address())
+                .typed(proxy::OnChainClaimContractProxy)
+                .get_referees(&address, from, MAX_REFEREES_PAGE_SIZE)
+                .returns(ReturnsResultUnmanaged)
+                .prepare_async()
+                .run()
+                .await
+                .into_vec();
+            for referee in &referees {
+                println!("{}", Bech32Address::from(referee));
+            }
+            if referees.len() < MAX_REFEREES_PAGE_SIZE {
+                break;
+            }
+            from += referees.len();
+        }
+    }
+
+    async fn repair_payment_sink(&mut self) {
+        let sink = self
+            .interactor
+            .query()
+            .to(self.state.current_address())
+            .typed(proxy::OnChainClaimContractProxy)
+            .get_repair_payment_sink()
+            .returns(ReturnsResultUnmanaged)
+            .prepare_async()
+            .run()
+            .await;
+
+        match sink {
+            proxy::RepairPaymentSink::Burn => println!("Burn"),
+            proxy::RepairPaymentSink::Treasury { treasury } => {
+                println!("Treasury {}", Bech32Address::from(treasury.to_address()))
+            }
+            proxy::RepairPaymentSink::Split {
+                treasury,
+                burn_percentage,
+            } => println!(
+                "Burn {burn_percentage}%, rest to treasury {}",
+                Bech32Address::from(treasury.to_address())
+            ),
+        }
+    }
+
+    async fn fetch_max_repair_gap(&mut self) -> u64 {
+        self.interactor
+            .query()
+            .to(self.state.current_address())
+            .typed(proxy::OnChainClaimContractProxy)
+            .get_max_repair_gap()
+            .returns(ReturnsResultUnmanaged)
+            .prepare_async()
+            .run()
+            .await
+    }
+
     async fn get_address_info(&mut self) {
         let address = bech32::decode("");
 
@@ -290,7 +961,8 @@ impl ContractInteract {
         println!("Result: {result_value:?}");
     }
 
-    async fn is_admin(&mut self) {
+    async fn has_role(&mut self) {
+        let role = proxy::Role::StateEditor;
         let address = bech32::decode("");
 
         let result_value = self
@@ -298,7 +970,7 @@ impl ContractInteract {
             .query()
             .to(self.state.current_address())
             .typed(proxy::OnChainClaimContractProxy)
-            .is_admin(address)
+            .has_role(role, address)
             .returns(ReturnsResultUnmanaged)
             .prepare_async()
             .run()
@@ -307,51 +979,85 @@ impl ContractInteract {
         println!("Result: {result_value:?}");
     }
 
-    async fn add_admin(&mut self) {
-        let address = bech32::decode("");
+    async fn role_holders(&mut self) {
+        let role = proxy::Role::StateEditor;
 
-        let response = self
+        let result_value = self
             .interactor
-            .tx()
-            .from(&self.wallet_address)
+            .query()
             .to(self.state.current_address())
-            .gas(NumExpr("30,000,000"))
             .typed(proxy::OnChainClaimContractProxy)
-            .add_admin(address)
+            .role_holders(role)
             .returns(ReturnsResultUnmanaged)
             .prepare_async()
             .run()
             .await;
 
+        println!("Result: {result_value:?}");
+    }
+
+    async fn pause(&mut self) {
+        let Some(response) = self
+            .gas
+            .run(
+                self.interactor
+                    .tx()
+                    .from(&self.wallet_address)
+                    .to(self.state.current_address())
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .pause()
+                    .returns(ReturnsResultUnmanaged),
+            )
+            .await
+        else {
+            return;
+        };
+
         println!("Result: {response:?}");
     }
 
-    async fn remove_admin(&mut self) {
-        let address = bech32::decode("");
+    async fn unpause(&mut self) {
+        let Some(response) = self
+            .gas
+            .run(
+                self.interactor
+                    .tx()
+                    .from(&self.wallet_address)
+                    .to(self.state.current_address())
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .unpause()
+                    .returns(ReturnsResultUnmanaged),
+            )
+            .await
+        else {
+            return;
+        };
 
-        let response = self
+        println!("Result: {response:?}");
+    }
+
+    async fn paused(&mut self) {
+        let result_value = self
             .interactor
-            .tx()
-            .from(&self.wallet_address)
+            .query()
             .to(self.state.current_address())
-            .gas(NumExpr("30,000,000"))
             .typed(proxy::OnChainClaimContractProxy)
-            .remove_admin(address)
+            .paused()
             .returns(ReturnsResultUnmanaged)
             .prepare_async()
             .run()
             .await;
 
-        println!("Result: {response:?}");
+        println!("Result: {result_value:?}");
     }
 
-    async fn admins(&mut self) {
+    async fn admin_action_count(&mut self) {
         let result_value = self
             .interactor
             .query()
             .to(self.state.current_address())
             .typed(proxy::OnChainClaimContractProxy)
-            .admins()
+            .admin_action_count()
             .returns(ReturnsResultUnmanaged)
             .prepare_async()
             .run()
@@ -359,4 +1065,265 @@ impl ContractInteract {
 
         println!("Result: {result_value:?}");
     }
+
+    async fn fetch_snapshot(&mut self) -> Snapshot {
+        let contract_address = self.state.current_address().clone();
+        let sdk_address =
+            sdk::data::address::Address::from_bytes(*contract_address.as_address().as_array());
+        let pairs = self
+            .interactor
+            .proxy
+            .get_account_storage_keys(&sdk_address)
+            .await
+            .expect("failed to fetch contract storage");
+
+        Snapshot::from_storage_pairs(contract_address, &pairs)
+    }
+
+    async fn export_snapshot(&mut self, path: &str) {
+        let snapshot = self.fetch_snapshot().await;
+        snapshot.save(path);
+
+        println!(
+            "Exported {} address info entries to {path}",
+            snapshot.entries.len()
+        );
+    }
+
+    async fn import_snapshot(&mut self, path: &str) {
+        let snapshot = Snapshot::load(path);
+
+        for batch in snapshot.entries.chunks(SNAPSHOT_IMPORT_BATCH_SIZE) {
+            self.update_state_batch(batch).await;
+        }
+        if self.gas.settings.dry_run {
+            return;
+        }
+        if self.fetch_state_override_quorum().await > 1 {
+            println!(
+                "Proposed {} state overrides from {path}, they are applied once approved by the quorum",
+                snapshot.entries.len()
+            );
+            return;
+        }
+
+        let imported = self.fetch_snapshot().await;
+        let mismatches = snapshot.diff(&imported);
+        for (expected, actual) in &mismatches {
+            println!("Mismatch: expected {expected:?}, found {actual:?}");
+        }
+
+        assert!(mismatches.is_empty(), "snapshot import verification failed");
+        println!(
+            "Imported {} address info entries from {path}",
+            snapshot.entries.len()
+        );
+    }
+
+    async fn update_state_batch(&mut self, entries: &[SnapshotEntry]) {
+        let mut gas_limits = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let estimate = self
+                .gas
+                .estimate(
+                    self.interactor
+                        .tx()
+                        .from(&self.wallet_address)
+                        .to(self.state.current_address())
+                        .typed(proxy::OnChainClaimContractProxy)
+                        .update_state(
+                            &entry.address,
+                            entry.current_streak,
+                            entry.last_epoch_claimed,
+                            entry.total_epochs_claimed,
+                            entry.best_streak,
+                        ),
+                )
+                .await;
+            println!("updateState for {} {estimate}", entry.address);
+            gas_limits.push(estimate.gas_limit);
+        }
+        if self.gas.settings.dry_run {
+            return;
+        }
+
+        let mut buffer = self.interactor.homogenous_call_buffer();
+        for (entry, gas_limit) in entries.iter().zip(gas_limits) {
+            buffer.push_tx(|tx| {
+                tx.from(&self.wallet_address)
+                    .to(self.state.current_address())
+                    .gas(gas_limit)
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .update_state(
+                        &entry.address,
+                        entry.current_streak,
+                        entry.last_epoch_claimed,
+                        entry.total_epochs_claimed,
+                        entry.best_streak,
+                    )
+                    .returns(ReturnsStatus)
+            });
+        }
+
+        let statuses = buffer.run().await;
+        for (entry, status) in entries.iter().zip(statuses) {
+            assert_eq!(status, 0, "updateState failed for {}", entry.address);
+        }
+    }
+
+    async fn index_events(&mut self, path: &str) {
+        let contract_address = self.state.current_address().clone();
+        let mut store = EventStore::open(path);
+        // Also covers the stores indexed before the cursor existed, and runs interrupted before saving it
+        let indexed_tx_hashes = store.indexed_tx_hashes();
+        let cursor_path = IndexCursor::path(path);
+        let mut cursor = IndexCursor::load(&cursor_path);
+
+        let sources =
+            indexer::fetch_contract_log_sources(API, &contract_address, cursor.timestamp).await;
+        let mut new_events = Vec::new();
+        for source in sources {
+            if cursor.is_processed(&source) || indexed_tx_hashes.contains(&source.hash) {
+                cursor.advance(&source);
+                continue;
+            }
+
+            let hash = &source.hash;
+            match source.kind {
+                LogSourceKind::Transaction => {
+                    let tx = self
+                        .interactor
+                        .proxy
+                        .get_transaction_info_with_results(hash)
+                        .await
+                        .expect("failed to fetch transaction");
+                    new_events.extend(claim_events::decode_transaction_events(
+                        &contract_address,
+                        hash,
+                        &tx,
+                    ));
+                }
+                LogSourceKind::Result => {
+                    let result = indexer::fetch_result(API, hash).await;
+                    // Results don't carry their epoch, it is the one of the original transaction
+                    let original_tx = self
+                        .interactor
+                        .proxy
+                        .get_transaction_info(&result.original_tx_hash)
+                        .await
+                        .expect("failed to fetch transaction");
+                    new_events.extend(claim_events::decode_log_events(
+                        &contract_address,
+                        hash,
+                        result.logs.as_ref(),
+                        original_tx.epoch,
+                        result.timestamp,
+                    ));
+                }
+            }
+            cursor.advance(&source);
+        }
+
+        println!("Indexed {} new events into {path}", new_events.len());
+        store.append(new_events);
+        cursor.save(&cursor_path);
+    }
+
+    fn address_history(&self, address: &str, path: &str) {
+        let address = Bech32Address::from_bech32_string(address.to_string());
+        let store = EventStore::open(path);
+
+        for event in store.address_history(&address) {
+            println!(
+                "epoch {}: {:?} {:?} (tx {})",
+                event.epoch, event.kind, event.address_info, event.tx_hash
+            );
+        }
+    }
+
+    async fn replay_events(&mut self, path: &str) {
+        let store = EventStore::open(path);
+        let mut replay = Replay::run(store.events());
+
+        let snapshot = self.fetch_snapshot().await;
+        replay.verify_storage(&snapshot);
+
+        for mismatch in &replay.mismatches {
+            println!("Mismatch: {mismatch}");
+        }
+
+        println!(
+            "Replayed {} events for {} addresses, {} mismatches found",
+            store.events().len(),
+            replay.address_infos.len(),
+            replay.mismatches.len()
+        );
+    }
+
+    /// Reads the raw `address_info` storage entry of an address, `None` if it never claimed.
+    async fn fetch_stored_address_info(&self, address: &Bech32Address) -> Option<AddressInfo> {
+        let mut key = ADDRESS_INFO_STORAGE_KEY.to_vec();
+        key.extend_from_slice(address.as_address().as_array());
+        let url = format!(
+            "{GATEWAY}/address/{}/key/{}",
+            self.state.current_address(),
+            hex::encode(key)
+        );
+        let response: serde_json::Value = reqwest::get(url)
+            .await
+            .expect("failed to fetch storage key")
+            .json()
+            .await
+            .expect("invalid storage key response");
+
+        let value = hex::decode(response["data"]["value"].as_str().unwrap_or_default())
+            .expect("invalid storage value hex");
+        if value.is_empty() {
+            return None;
+        }
+
+        Some(AddressInfo::top_decode(value).expect("invalid address info"))
+    }
+
+    /// The metachain epoch, which the shards follow closely enough for previews.
+    async fn fetch_current_epoch(&self) -> u64 {
+        let url = format!("{GATEWAY}/network/status/{METACHAIN_SHARD_ID}");
+        let response: serde_json::Value = reqwest::get(url)
+            .await
+            .expect("failed to fetch network status")
+            .json()
+            .await
+            .expect("invalid network status response");
+
+        response["data"]["status"]["erd_epoch_number"]
+            .as_u64()
+            .expect("missing epoch in network status")
+    }
+
+    /// Shows what claim and claimAndRepair would do for an address in the current epoch, without sending anything.
+    async fn preview_claim(&mut self, address: &str) {
+        let address = Bech32Address::from_bech32_string(address.to_string());
+        let address_info = self.fetch_stored_address_info(&address).await;
+        let current_epoch = self.fetch_current_epoch().await;
+        // Same margin as the contract gives over the configured gap
+        let max_repair_gap_on_claim = self.fetch_max_repair_gap().await + 1;
+
+        println!("Epoch {current_epoch}, current state: {address_info:?}");
+        match AddressInfo::claim(address_info.as_ref(), current_epoch) {
+            Ok((address_info, outcome)) => {
+                println!("claim: {outcome:?}, new state: {address_info:?}")
+            }
+            Err(err) => println!("claim: fails with \"{}\"", err.message()),
+        }
+        match AddressInfo::claim_and_repair(
+            address_info.as_ref(),
+            current_epoch,
+            max_repair_gap_on_claim,
+        ) {
+            Ok((address_info, missed_epochs)) => println!(
+                "claimAndRepair: repairs {missed_epochs} epochs, new state: {address_info:?}"
+            ),
+            Err(err) => println!("claimAndRepair: fails with \"{}\"", err.message()),
+        }
+    }
 }
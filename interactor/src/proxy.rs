@@ -97,6 +97,58 @@ where
             .original_result()
     }
 
+    /// First claim of the caller, whose streak milestones earn points to `referrer`. 
+    pub fn claim_with_referrer<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        referrer: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("claimWithReferrer")
+            .argument(&referrer)
+            .original_result()
+    }
+
+    /// Claims for `beneficiary`, who authorized the caller through `authorizeDelegate`. 
+    pub fn claim_for<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        beneficiary: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("claimFor")
+            .argument(&beneficiary)
+            .original_result()
+    }
+
+    /// Claims for `signer`, who signed the claim message for the current epoch and its next nonce. 
+    /// Unlike the other claims, the signer can be in any shard. 
+    pub fn claim_with_signature<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+        Arg1: ProxyArg<u64>,
+        Arg2: ProxyArg<u64>,
+        Arg3: ProxyArg<ManagedByteArray<Env::Api, 64usize>>,
+    >(
+        self,
+        signer: Arg0,
+        epoch: Arg1,
+        nonce: Arg2,
+        signature: Arg3,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("claimWithSignature")
+            .argument(&signer)
+            .argument(&epoch)
+            .argument(&nonce)
+            .argument(&signature)
+            .original_result()
+    }
+
     pub fn claim_and_repair(
         self,
     ) -> TxTypedCall<Env, From, To, (), Gas, ()> {
@@ -105,6 +157,7 @@ where
             .original_result()
     }
 
+    /// Applied directly, or proposed for the approval of the other state editors in quorum mode. 
     pub fn update_state<
         Arg0: ProxyArg<ManagedAddress<Env::Api>>,
         Arg1: ProxyArg<u64>,
@@ -130,22 +183,6 @@ where
             .original_result()
     }
 
-    pub fn set_repair_streak_payment<
-        Arg0: ProxyArg<TokenIdentifier<Env::Api>>,
-        Arg1: ProxyArg<u64>,
-    >(
-        self,
-        repair_streak_token_identifier: Arg0,
-        repair_streak_token_nonce: Arg1,
-    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
-        self.wrapped_tx
-            .payment(NotPayable)
-            .raw_call("setRepairStreakPayment")
-            .argument(&repair_streak_token_identifier)
-            .argument(&repair_streak_token_nonce)
-            .original_result()
-    }
-
     pub fn get_address_info<
         Arg0: ProxyArg<ManagedAddress<Env::Api>>,
     >(
@@ -172,16 +209,69 @@ where
             .original_result()
     }
 
+    pub fn get_max_repair_gap(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, u64> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getMaxRepairGap")
+            .original_result()
+    }
+
+    pub fn get_repair_payment_sink(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, RepairPaymentSink<Env::Api>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getRepairPaymentSink")
+            .original_result()
+    }
+
+    /// Same encoding as the `EsdtTokenPayment` stored before EGLD payments, for ESDTs. 
     pub fn repair_streak_payment(
         self,
-    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, EsdtTokenPayment<Env::Api>> {
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, EgldOrEsdtTokenPayment<Env::Api>> {
         self.wrapped_tx
             .payment(NotPayable)
             .raw_call("getRepairStreakPayment")
             .original_result()
     }
 
-    pub fn is_admin<
+    /// Whether the streak of the address is at least `min_streak` epochs and not broken by a missed epoch. 
+    pub fn has_min_streak<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+        Arg1: ProxyArg<u64>,
+    >(
+        self,
+        address: Arg0,
+        min_streak: Arg1,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, bool> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("hasMinStreak")
+            .argument(&address)
+            .argument(&min_streak)
+            .original_result()
+    }
+
+    /// Whether the address claimed in one of the last `epochs` epochs, the current one included. 
+    pub fn has_claimed_in_last_epochs<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+        Arg1: ProxyArg<u64>,
+    >(
+        self,
+        address: Arg0,
+        epochs: Arg1,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, bool> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("hasClaimedInLastEpochs")
+            .argument(&address)
+            .argument(&epochs)
+            .original_result()
+    }
+
+    pub fn is_active_claimer<
         Arg0: ProxyArg<ManagedAddress<Env::Api>>,
     >(
         self,
@@ -189,12 +279,170 @@ where
     ) -> TxTypedCall<Env, From, To, NotPayable, Gas, bool> {
         self.wrapped_tx
             .payment(NotPayable)
-            .raw_call("isAdmin")
+            .raw_call("isActiveClaimer")
+            .argument(&address)
+            .original_result()
+    }
+
+    pub fn pause(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("pause")
+            .original_result()
+    }
+
+    pub fn unpause(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("unpause")
+            .original_result()
+    }
+
+    pub fn has_role<
+        Arg0: ProxyArg<Role>,
+        Arg1: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        role: Arg0,
+        address: Arg1,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, bool> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("hasRole")
+            .argument(&role)
+            .argument(&address)
+            .original_result()
+    }
+
+    pub fn role_holders<
+        Arg0: ProxyArg<Role>,
+    >(
+        self,
+        role: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, ManagedAddress<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getRoleHolders")
+            .argument(&role)
+            .original_result()
+    }
+
+    pub fn paused(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, bool> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("isPaused")
+            .original_result()
+    }
+
+    pub fn admin_action_count(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, u64> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getAdminActionCount")
+            .original_result()
+    }
+
+    pub fn open_season<
+        Arg0: ProxyArg<ManagedBuffer<Env::Api>>,
+        Arg1: ProxyArg<u64>,
+        Arg2: ProxyArg<u64>,
+    >(
+        self,
+        name: Arg0,
+        start_epoch: Arg1,
+        end_epoch: Arg2,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, u64> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("openSeason")
+            .argument(&name)
+            .argument(&start_epoch)
+            .argument(&end_epoch)
+            .original_result()
+    }
+
+    /// Ends a season with the current epoch, the last one in which claims still count for it. 
+    pub fn close_season<
+        Arg0: ProxyArg<u64>,
+    >(
+        self,
+        season_id: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("closeSeason")
+            .argument(&season_id)
+            .original_result()
+    }
+
+    pub fn get_seasons(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, Season<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getSeasons")
+            .original_result()
+    }
+
+    pub fn get_current_season(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, OptionalValue<Season<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getCurrentSeason")
+            .original_result()
+    }
+
+    pub fn get_season_stats<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+        Arg1: ProxyArg<u64>,
+    >(
+        self,
+        address: Arg0,
+        season_id: Arg1,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, SeasonStats> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getSeasonStats")
             .argument(&address)
+            .argument(&season_id)
+            .original_result()
+    }
+
+    pub fn authorize_delegate<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        delegate: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("authorizeDelegate")
+            .argument(&delegate)
+            .original_result()
+    }
+
+    pub fn revoke_delegate<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        delegate: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("revokeDelegate")
+            .argument(&delegate)
             .original_result()
     }
 
-    pub fn add_admin<
+    pub fn add_allowed_contract<
         Arg0: ProxyArg<ManagedAddress<Env::Api>>,
     >(
         self,
@@ -202,12 +450,12 @@ where
     ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
         self.wrapped_tx
             .payment(NotPayable)
-            .raw_call("addAdmin")
+            .raw_call("addAllowedContract")
             .argument(&address)
             .original_result()
     }
 
-    pub fn remove_admin<
+    pub fn remove_allowed_contract<
         Arg0: ProxyArg<ManagedAddress<Env::Api>>,
     >(
         self,
@@ -215,26 +463,752 @@ where
     ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
         self.wrapped_tx
             .payment(NotPayable)
-            .raw_call("removeAdmin")
+            .raw_call("removeAllowedContract")
+            .argument(&address)
+            .original_result()
+    }
+
+    pub fn is_delegate<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+        Arg1: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        beneficiary: Arg0,
+        delegate: Arg1,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, bool> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("isDelegate")
+            .argument(&beneficiary)
+            .argument(&delegate)
+            .original_result()
+    }
+
+    pub fn is_allowed_contract<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        address: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, bool> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("isAllowedContract")
             .argument(&address)
             .original_result()
     }
 
-    pub fn admins(
+    pub fn delegates<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
         self,
+        beneficiary: Arg0,
     ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, ManagedAddress<Env::Api>>> {
         self.wrapped_tx
             .payment(NotPayable)
-            .raw_call("getAdmins")
+            .raw_call("getDelegates")
+            .argument(&beneficiary)
             .original_result()
     }
-}
 
-#[type_abi]
-#[derive(Default, NestedEncode, NestedDecode, TopEncode, TopDecode)]
-pub struct AddressInfo {
-    pub current_streak: u64,
-    pub last_epoch_claimed: u64,
-    pub total_epochs_claimed: u64,
+    pub fn beneficiaries<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        delegate: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, ManagedAddress<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getBeneficiaries")
+            .argument(&delegate)
+            .original_result()
+    }
+
+    pub fn allowed_contracts(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, ManagedAddress<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getAllowedContracts")
+            .original_result()
+    }
+
+    pub fn next_claim_nonce<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        signer: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, u64> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getNextClaimNonce")
+            .argument(&signer)
+            .original_result()
+    }
+
+    /// A quorum of 0 or 1 applies state overrides directly. 
+    pub fn set_state_override_quorum<
+        Arg0: ProxyArg<u32>,
+        Arg1: ProxyArg<u64>,
+    >(
+        self,
+        quorum: Arg0,
+        expiry_epochs: Arg1,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("setStateOverrideQuorum")
+            .argument(&quorum)
+            .argument(&expiry_epochs)
+            .original_result()
+    }
+
+    pub fn approve_state_override<
+        Arg0: ProxyArg<u64>,
+    >(
+        self,
+        id: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("approveStateOverride")
+            .argument(&id)
+            .original_result()
+    }
+
+    /// Removes a pending or expired proposal, by its proposer or the owner. 
+    pub fn cancel_state_override<
+        Arg0: ProxyArg<u64>,
+    >(
+        self,
+        id: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("cancelStateOverride")
+            .argument(&id)
+            .original_result()
+    }
+
+    /// Pending proposals, including expired ones until they are cancelled. 
+    pub fn get_pending_state_overrides(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, StateOverrideProposal<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getPendingStateOverrides")
+            .original_result()
+    }
+
+    pub fn state_override_quorum(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, u32> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getStateOverrideQuorum")
+            .original_result()
+    }
+
+    pub fn state_override_expiry_epochs(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, u64> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getStateOverrideExpiryEpochs")
+            .original_result()
+    }
+
+    pub fn state_override_approvals<
+        Arg0: ProxyArg<u64>,
+    >(
+        self,
+        id: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, ManagedAddress<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getStateOverrideApprovals")
+            .argument(&id)
+            .original_result()
+    }
+
+    pub fn set_claim_history_size<
+        Arg0: ProxyArg<u32>,
+    >(
+        self,
+        size: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("setClaimHistorySize")
+            .argument(&size)
+            .original_result()
+    }
+
+    /// The entries of an address, oldest first. 
+    pub fn get_claim_history<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        address: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, ClaimHistoryEntry>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getClaimHistory")
+            .argument(&address)
+            .original_result()
+    }
+
+    pub fn get_claim_history_size(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, u32> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getClaimHistorySize")
+            .original_result()
+    }
+
+    /// The words covering `start_epoch..=end_epoch`, with the epochs out of the range cleared. 
+    pub fn get_claim_bitmap<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+        Arg1: ProxyArg<u64>,
+        Arg2: ProxyArg<u64>,
+    >(
+        self,
+        address: Arg0,
+        start_epoch: Arg1,
+        end_epoch: Arg2,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, ClaimBitmapWord>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getClaimBitmap")
+            .argument(&address)
+            .argument(&start_epoch)
+            .argument(&end_epoch)
+            .original_result()
+    }
+
+    /// Issues the collection and gives the contract all its roles, paid with the issue cost in EGLD. 
+    pub fn issue_badge_collection<
+        Arg0: ProxyArg<ManagedBuffer<Env::Api>>,
+        Arg1: ProxyArg<ManagedBuffer<Env::Api>>,
+    >(
+        self,
+        token_display_name: Arg0,
+        token_ticker: Arg1,
+    ) -> TxTypedCall<Env, From, To, (), Gas, ()> {
+        self.wrapped_tx
+            .raw_call("issueBadgeCollection")
+            .argument(&token_display_name)
+            .argument(&token_ticker)
+            .original_result()
+    }
+
+    pub fn set_badge_tiers<
+        Arg0: ProxyArg<u64>,
+        Arg1: ProxyArg<u64>,
+        Arg2: ProxyArg<u64>,
+    >(
+        self,
+        bronze: Arg0,
+        silver: Arg1,
+        gold: Arg2,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("setBadgeTiers")
+            .argument(&bronze)
+            .argument(&silver)
+            .argument(&gold)
+            .original_result()
+    }
+
+    pub fn get_badge<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        address: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, OptionalValue<BadgeAttributes<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getBadge")
+            .argument(&address)
+            .original_result()
+    }
+
+    pub fn get_badge_tiers(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, BadgeTiers> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getBadgeTiers")
+            .original_result()
+    }
+
+    pub fn badge_token(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, TokenIdentifier<Env::Api>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getBadgeTokenId")
+            .original_result()
+    }
+
+    /// Nonce of the badge of an address, 0 when it has none. 
+    pub fn badge_nonce<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        address: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, u64> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getBadgeNonce")
+            .argument(&address)
+            .original_result()
+    }
+
+    /// Replaces the milestones, which must have increasing streaks. Already rewarded streaks are not rewarded again. 
+    pub fn set_referral_milestones<
+        Arg0: ProxyArg<MultiValueEncoded<Env::Api, MultiValue2<u64, u64>>>,
+    >(
+        self,
+        milestones: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("setReferralMilestones")
+            .argument(&milestones)
+            .original_result()
+    }
+
+    /// Up to `size` referees of `referrer`, in the order they were referred, starting from index `from`. 
+    pub fn get_referees<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+        Arg1: ProxyArg<usize>,
+        Arg2: ProxyArg<usize>,
+    >(
+        self,
+        referrer: Arg0,
+        from: Arg1,
+        size: Arg2,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, ManagedAddress<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getReferees")
+            .argument(&referrer)
+            .argument(&from)
+            .argument(&size)
+            .original_result()
+    }
+
+    pub fn get_referee_count<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        referrer: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, usize> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getRefereeCount")
+            .argument(&referrer)
+            .original_result()
+    }
+
+    pub fn get_referral_milestones(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, ReferralMilestone>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getReferralMilestones")
+            .original_result()
+    }
+
+    pub fn get_referrer<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        referee: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, OptionalValue<ManagedAddress<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getReferrer")
+            .argument(&referee)
+            .original_result()
+    }
+
+    pub fn referral_points<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        referrer: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, u64> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getReferralPoints")
+            .argument(&referrer)
+            .original_result()
+    }
+
+    pub fn propose_config_change<
+        Arg0: ProxyArg<ConfigChange<Env::Api>>,
+    >(
+        self,
+        change: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, u64> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("proposeConfigChange")
+            .argument(&change)
+            .original_result()
+    }
+
+    pub fn execute_config_change<
+        Arg0: ProxyArg<u64>,
+    >(
+        self,
+        id: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("executeConfigChange")
+            .argument(&id)
+            .original_result()
+    }
+
+    /// Proposals can be cancelled by the same roles that can execute them, and by the owner. 
+    /// Role changes can only be cancelled by the owner and their proposer, 
+    /// so that a compromised role manager can't cancel its own revocation. 
+    pub fn cancel_config_change<
+        Arg0: ProxyArg<u64>,
+    >(
+        self,
+        id: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("cancelConfigChange")
+            .argument(&id)
+            .original_result()
+    }
+
+    pub fn get_timelock_epochs(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, u64> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getTimelockEpochs")
+            .original_result()
+    }
+
+    pub fn get_pending_config_changes(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, ConfigChangeProposal<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getPendingConfigChanges")
+            .original_result()
+    }
+
+    pub fn get_config_change<
+        Arg0: ProxyArg<u64>,
+    >(
+        self,
+        id: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, OptionalValue<ConfigChangeProposal<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getConfigChange")
+            .argument(&id)
+            .original_result()
+    }
+}
+
+#[type_abi]
+#[derive(Default, NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct AddressInfo {
+    pub current_streak: u64,
+    pub last_epoch_claimed: u64,
+    pub total_epochs_claimed: u64,
+    pub best_streak: u64,
+}
+
+#[rustfmt::skip]
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub enum RepairPaymentSink<Api>
+where
+    Api: ManagedTypeApi,
+{
+    Burn,
+    Treasury {
+        treasury: ManagedAddress<Api>,
+    },
+    Split {
+        treasury: ManagedAddress<Api>,
+        burn_percentage: u64,
+    },
+}
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct AddressInfoUpdate {
+    pub previous: AddressInfo,
+    pub current: AddressInfo,
+}
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct ConfigChangeProposal<Api>
+where
+    Api: ManagedTypeApi,
+{
+    pub id: u64,
+    pub change: ConfigChange<Api>,
+    pub proposer: ManagedAddress<Api>,
+    pub proposed_epoch: u64,
+    pub executable_epoch: u64,
+}
+
+#[rustfmt::skip]
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub enum ConfigChange<Api>
+where
+    Api: ManagedTypeApi,
+{
+    SetRepairStreakPayment {
+        token_identifier: TokenIdentifier<Api>,
+        token_nonce: u64,
+    },
+    SetMaxRepairGap {
+        max_repair_gap: u64,
+    },
+    GrantRole {
+        role: Role,
+        address: ManagedAddress<Api>,
+    },
+    RevokeRole {
+        role: Role,
+        address: ManagedAddress<Api>,
+    },
+    SetTimelockEpochs {
+        timelock_epochs: u64,
+    },
+    SetRepairPaymentSink {
+        sink: RepairPaymentSink<Api>,
+    },
+    SetRepairPayment {
+        payment: EgldOrEsdtTokenPayment<Api>,
+    },
+}
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, Copy, PartialEq, Debug)]
+pub enum Role {
+    StateEditor,
+    PricingManager,
+    Pauser,
+    RoleManager,
+}
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct StateOverrideProposal<Api>
+where
+    Api: ManagedTypeApi,
+{
+    pub id: u64,
+    pub address: ManagedAddress<Api>,
+    pub address_info: AddressInfo,
+    pub proposer: ManagedAddress<Api>,
+    pub proposed_epoch: u64,
+    pub expiry_epoch: u64,
+}
+
+#[type_abi]
+#[derive(
+    NestedEncode,
+    NestedDecode,
+    TopEncode,
+    TopDecode,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Debug,
+)]
+pub enum BadgeTier {
+    Bronze,
+    Silver,
+    Gold,
+}
+
+#[rustfmt::skip]
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode)]
+pub enum AdminAction<Api>
+where
+    Api: ManagedTypeApi,
+{
+    UpdateState {
+        address: ManagedAddress<Api>,
+        previous: AddressInfo,
+        current: AddressInfo,
+    },
+    SetRepairStreakPayment {
+        previous: EgldOrEsdtTokenPayment<Api>,
+        current: EgldOrEsdtTokenPayment<Api>,
+    },
+    AddAdmin {
+        address: ManagedAddress<Api>,
+        was_admin: bool,
+    },
+    RemoveAdmin {
+        address: ManagedAddress<Api>,
+        was_admin: bool,
+    },
+    OpenSeason {
+        season: Season<Api>,
+    },
+    CloseSeason {
+        season_id: u64,
+        previous_end_epoch: u64,
+        end_epoch: u64,
+    },
+    AddAllowedContract {
+        address: ManagedAddress<Api>,
+        was_allowed: bool,
+    },
+    RemoveAllowedContract {
+        address: ManagedAddress<Api>,
+        was_allowed: bool,
+    },
+    GrantRole {
+        role: Role,
+        address: ManagedAddress<Api>,
+        had_role: bool,
+    },
+    RevokeRole {
+        role: Role,
+        address: ManagedAddress<Api>,
+        had_role: bool,
+    },
+    SetPaused {
+        paused: bool,
+        was_paused: bool,
+    },
+    SetMaxRepairGap {
+        previous: u64,
+        current: u64,
+    },
+    SetTimelockEpochs {
+        previous: u64,
+        current: u64,
+    },
+    SetStateOverrideQuorum {
+        previous_quorum: u32,
+        quorum: u32,
+        previous_expiry_epochs: u64,
+        expiry_epochs: u64,
+    },
+    SetClaimHistorySize {
+        previous: u32,
+        current: u32,
+    },
+    SetBadgeTiers {
+        previous: BadgeTiers,
+        current: BadgeTiers,
+    },
+    SetReferralMilestones {
+        previous: ManagedVec<Api, ReferralMilestone>,
+        current: ManagedVec<Api, ReferralMilestone>,
+    },
+    SetRepairPaymentSink {
+        previous: RepairPaymentSink<Api>,
+        current: RepairPaymentSink<Api>,
+    },
+    ApproveStateOverride {
+        id: u64,
+    },
+    CancelStateOverride {
+        proposal: StateOverrideProposal<Api>,
+    },
+    CancelConfigChange {
+        proposal: ConfigChangeProposal<Api>,
+    },
+}
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct Season<Api>
+where
+    Api: ManagedTypeApi,
+{
+    pub id: u64,
+    pub name: ManagedBuffer<Api>,
+    pub start_epoch: u64,
+    pub end_epoch: u64,
+}
+
+#[type_abi]
+#[derive(Default, NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct BadgeTiers {
+    pub bronze: u64,
+    pub silver: u64,
+    pub gold: u64,
+}
+
+#[type_abi]
+#[derive(
+    ManagedVecItem, NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug,
+)]
+pub struct ReferralMilestone {
+    pub streak: u64,
+    pub points: u64,
+}
+
+#[type_abi]
+#[derive(Default, NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct SeasonStats {
+    pub season_id: u64,
+    pub current_streak: u64,
+    pub last_epoch_claimed: u64,
+    pub epochs_claimed: u64,
+    pub best_streak: u64,
+}
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct ClaimHistoryEntry {
+    pub epoch: u64,
+    pub kind: ClaimHistoryKind,
+}
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, Copy, PartialEq, Debug)]
+pub enum ClaimHistoryKind {
+    Claim,
+    Repair,
+    AdminSet,
+}
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct ClaimBitmapWord {
+    pub first_epoch: u64,
+    pub claimed: u64,
+    pub repaired: u64,
+}
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct BadgeAttributes<Api>
+where
+    Api: ManagedTypeApi,
+{
+    pub owner: ManagedAddress<Api>,
+    pub tier: BadgeTier,
     pub best_streak: u64,
+    pub tier_epoch: u64,
 }
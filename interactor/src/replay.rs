@@ -0,0 +1,397 @@
+use crate::claim_events::{AddressInfoRecord, ClaimEvent, ClaimEventKind};
+use crate::snapshot::Snapshot;
+use on_chain_claim::{
+    config::{MAX_ALLOWED_REPAIR_GAP, MAX_REPAIR_GAP_ON_CLAIM},
+    AddressInfo,
+};
+use std::{collections::BTreeMap, fmt};
+
+/// A discrepancy found while replaying the event stream or comparing its result to the live storage.
+#[derive(Debug, PartialEq)]
+pub enum ReplayMismatch {
+    /// The event could not have been produced by the contract, given the previously replayed events.
+    InvalidTransition {
+        tx_hash: String,
+        address: String,
+        reason: &'static str,
+    },
+    /// The previous state carried by the event differs from the replayed one, some history is missing.
+    PreviousState {
+        tx_hash: String,
+        address: String,
+        replayed: AddressInfoRecord,
+        found: AddressInfoRecord,
+    },
+    /// The replayed state differs from the one carried by the event.
+    EventState {
+        tx_hash: String,
+        address: String,
+        expected: AddressInfoRecord,
+        found: AddressInfoRecord,
+    },
+    /// The replayed state differs from the one currently in the contract storage.
+    Storage {
+        address: String,
+        replayed: Option<AddressInfoRecord>,
+        stored: Option<AddressInfoRecord>,
+    },
+}
+
+impl fmt::Display for ReplayMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayMismatch::InvalidTransition {
+                tx_hash,
+                address,
+                reason,
+            } => write!(
+                f,
+                "tx {tx_hash} for {address}: invalid transition, {reason}"
+            ),
+            ReplayMismatch::PreviousState {
+                tx_hash,
+                address,
+                replayed,
+                found,
+            } => write!(
+                f,
+                "tx {tx_hash} for {address}: replayed {replayed:?}, event previous state is {found:?}"
+            ),
+            ReplayMismatch::EventState {
+                tx_hash,
+                address,
+                expected,
+                found,
+            } => write!(
+                f,
+                "tx {tx_hash} for {address}: replayed {expected:?}, event carries {found:?}"
+            ),
+            ReplayMismatch::Storage {
+                address,
+                replayed,
+                stored,
+            } => write!(
+                f,
+                "storage of {address}: replayed {replayed:?}, stored {stored:?}"
+            ),
+        }
+    }
+}
+
+/// Off-chain reconstruction of the contract's `address_info` storage from its events.
+pub struct Replay {
+    pub address_infos: BTreeMap<String, AddressInfoRecord>,
+    pub mismatches: Vec<ReplayMismatch>,
+}
+
+impl Replay {
+    pub fn run<'a, I: IntoIterator<Item = &'a ClaimEvent>>(events: I) -> Self {
+        let mut replay = Replay {
+            address_infos: BTreeMap::new(),
+            mismatches: Vec::new(),
+        };
+        for event in events {
+            replay.apply(event);
+        }
+
+        replay
+    }
+
+    pub fn apply(&mut self, event: &ClaimEvent) {
+        let (Some(address), Some(emitted)) = (&event.address, &event.address_info) else {
+            return;
+        };
+        let address = address.to_bech32_string();
+
+        let previous = self.address_infos.get(&address);
+        if let Some(emitted_previous) = &event.previous_address_info {
+            let replayed_previous = previous.cloned().unwrap_or_default();
+            if replayed_previous != *emitted_previous {
+                self.mismatches.push(ReplayMismatch::PreviousState {
+                    tx_hash: event.tx_hash.clone(),
+                    address: address.clone(),
+                    replayed: replayed_previous,
+                    found: emitted_previous.clone(),
+                });
+            }
+        }
+
+        let previous = previous.cloned().map(AddressInfo::from);
+        let replayed = match event.kind {
+            ClaimEventKind::Claim
+            | ClaimEventKind::DelegatedClaim
+            | ClaimEventKind::SignedClaim => AddressInfo::claim(previous.as_ref(), event.epoch)
+                .map(|(address_info, _)| address_info.into()),
+            ClaimEventKind::ClaimAndRepair => AddressInfo::claim_and_repair(
+                previous.as_ref(),
+                event.epoch,
+                max_missed_epochs(event),
+            )
+            .map(|(address_info, _)| address_info.into()),
+            ClaimEventKind::UpdateState => Ok(emitted.clone()),
+            ClaimEventKind::SetRepairPayment => return,
+        };
+
+        let replayed = match replayed {
+            Ok(replayed) => replayed,
+            Err(reason) => {
+                self.mismatches.push(ReplayMismatch::InvalidTransition {
+                    tx_hash: event.tx_hash.clone(),
+                    address: address.clone(),
+                    reason: reason.message(),
+                });
+                emitted.clone()
+            }
+        };
+
+        if replayed != *emitted {
+            self.mismatches.push(ReplayMismatch::EventState {
+                tx_hash: event.tx_hash.clone(),
+                address: address.clone(),
+                expected: replayed,
+                found: emitted.clone(),
+            });
+        }
+
+        // Continue from the emitted state, so that a single divergence doesn't cascade into the following events
+        self.address_infos.insert(address, emitted.clone());
+    }
+
+    /// Compares the replayed state with a snapshot of the contract storage.
+    pub fn verify_storage(&mut self, snapshot: &Snapshot) {
+        let stored: BTreeMap<String, AddressInfoRecord> = snapshot
+            .entries
+            .iter()
+            .map(|entry| (entry.address.to_bech32_string(), entry.address_info()))
+            .collect();
+
+        let mut addresses: Vec<&String> = self.address_infos.keys().chain(stored.keys()).collect();
+        addresses.sort();
+        addresses.dedup();
+
+        for address in addresses {
+            let replayed = self.address_infos.get(address);
+            let stored = stored.get(address);
+            if replayed != stored {
+                self.mismatches.push(ReplayMismatch::Storage {
+                    address: address.clone(),
+                    replayed: replayed.cloned(),
+                    stored: stored.cloned(),
+                });
+            }
+        }
+    }
+}
+
+/// The repair gap a repair is replayed with, since the gap can change through config changes.
+///
+/// Repairs are allowed up to the epochs they report, which can't be more than the highest gap a config change can set.
+/// A repair reporting other epochs than the replayed ones then shows up as an `EventState` mismatch.
+/// Events emitted before they reported their repaired epochs are from before the gap could be changed.
+fn max_missed_epochs(event: &ClaimEvent) -> u64 {
+    match event.missed_epochs {
+        Some(missed_epochs) => missed_epochs.min(MAX_ALLOWED_REPAIR_GAP + 1),
+        None => MAX_REPAIR_GAP_ON_CLAIM,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::SnapshotEntry;
+    use multiversx_sc_snippets::imports::{Address, Bech32Address};
+
+    fn address() -> Bech32Address {
+        Bech32Address::from(Address::from([1; 32]))
+    }
+
+    fn event(
+        kind: ClaimEventKind,
+        epoch: u64,
+        missed_epochs: Option<u64>,
+        address_info: AddressInfo,
+    ) -> ClaimEvent {
+        ClaimEvent {
+            tx_hash: format!("tx-{epoch}"),
+            epoch,
+            timestamp: epoch,
+            kind,
+            address: Some(address()),
+            address_info: Some(address_info.into()),
+            previous_address_info: None,
+            missed_epochs,
+            payment: None,
+            delegate: None,
+        }
+    }
+
+    /// A claim carrying the state it updated, as emitted since claims report their previous state.
+    fn claim(epoch: u64, previous: AddressInfo, address_info: AddressInfo) -> ClaimEvent {
+        ClaimEvent {
+            previous_address_info: Some(previous.into()),
+            ..event(ClaimEventKind::Claim, epoch, None, address_info)
+        }
+    }
+
+    #[test]
+    fn consistent_claims_replay_without_mismatches() {
+        let events = [
+            claim(1, AddressInfo::default(), AddressInfo::new(1, 1, 1, 1)),
+            claim(
+                2,
+                AddressInfo::new(1, 1, 1, 1),
+                AddressInfo::new(2, 2, 2, 2),
+            ),
+        ];
+
+        let replay = Replay::run(&events);
+        assert!(replay.mismatches.is_empty(), "{:?}", replay.mismatches);
+        assert_eq!(
+            replay.address_infos.get(&address().to_bech32_string()),
+            Some(&AddressInfo::new(2, 2, 2, 2).into())
+        );
+    }
+
+    #[test]
+    fn missing_events_are_previous_state_mismatches() {
+        // the claim of epoch 2 was never indexed
+        let events = [
+            claim(1, AddressInfo::default(), AddressInfo::new(1, 1, 1, 1)),
+            claim(
+                3,
+                AddressInfo::new(2, 2, 2, 2),
+                AddressInfo::new(3, 3, 3, 3),
+            ),
+        ];
+
+        let replay = Replay::run(&events);
+        assert_eq!(
+            replay.mismatches[0],
+            ReplayMismatch::PreviousState {
+                tx_hash: "tx-3".to_string(),
+                address: address().to_bech32_string(),
+                replayed: AddressInfo::new(1, 1, 1, 1).into(),
+                found: AddressInfo::new(2, 2, 2, 2).into(),
+            }
+        );
+        // replayed from the last known state, epoch 2 being missed
+        assert_eq!(
+            replay.mismatches[1..],
+            [ReplayMismatch::EventState {
+                tx_hash: "tx-3".to_string(),
+                address: address().to_bech32_string(),
+                expected: AddressInfo::new(1, 3, 2, 1).into(),
+                found: AddressInfo::new(3, 3, 3, 3).into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn tampered_states_are_event_state_mismatches() {
+        let events = [
+            claim(1, AddressInfo::default(), AddressInfo::new(1, 1, 1, 1)),
+            claim(
+                2,
+                AddressInfo::new(1, 1, 1, 1),
+                AddressInfo::new(5, 2, 2, 5),
+            ),
+            claim(
+                3,
+                AddressInfo::new(5, 2, 2, 5),
+                AddressInfo::new(6, 3, 3, 6),
+            ),
+        ];
+
+        let replay = Replay::run(&events);
+        // the following claim continues from the emitted state
+        assert_eq!(
+            replay.mismatches,
+            [ReplayMismatch::EventState {
+                tx_hash: "tx-2".to_string(),
+                address: address().to_bech32_string(),
+                expected: AddressInfo::new(2, 2, 2, 2).into(),
+                found: AddressInfo::new(5, 2, 2, 5).into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn differing_snapshots_are_storage_mismatches() {
+        let events = [claim(
+            1,
+            AddressInfo::default(),
+            AddressInfo::new(1, 1, 1, 1),
+        )];
+        let other_address = Bech32Address::from(Address::from([2; 32]));
+        let snapshot = Snapshot::new(
+            other_address.clone(),
+            vec![
+                SnapshotEntry::new(address(), &AddressInfo::new(2, 2, 2, 2)),
+                SnapshotEntry::new(other_address.clone(), &AddressInfo::new(1, 1, 1, 1)),
+            ],
+        );
+
+        let mut replay = Replay::run(&events);
+        replay.verify_storage(&snapshot);
+        assert_eq!(
+            replay.mismatches,
+            [
+                ReplayMismatch::Storage {
+                    address: other_address.to_bech32_string(),
+                    replayed: None,
+                    stored: Some(AddressInfo::new(1, 1, 1, 1).into()),
+                },
+                ReplayMismatch::Storage {
+                    address: address().to_bech32_string(),
+                    replayed: Some(AddressInfo::new(1, 1, 1, 1).into()),
+                    stored: Some(AddressInfo::new(2, 2, 2, 2).into()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn repairs_use_their_own_gap() {
+        // 10 missed epochs, repaired while a config change allowed it
+        let events = [
+            event(ClaimEventKind::Claim, 1, None, AddressInfo::new(1, 1, 1, 1)),
+            event(
+                ClaimEventKind::ClaimAndRepair,
+                12,
+                Some(10),
+                AddressInfo::new(12, 12, 12, 12),
+            ),
+        ];
+
+        let replay = Replay::run(&events);
+        assert!(replay.mismatches.is_empty(), "{:?}", replay.mismatches);
+    }
+
+    #[test]
+    fn repairs_beyond_the_gap_are_invalid() {
+        let too_late = MAX_ALLOWED_REPAIR_GAP + 3;
+        let events = [
+            event(ClaimEventKind::Claim, 1, None, AddressInfo::new(1, 1, 1, 1)),
+            // emitted before repairs reported their missed epochs, when the gap was fixed
+            event(
+                ClaimEventKind::ClaimAndRepair,
+                12,
+                None,
+                AddressInfo::new(12, 12, 12, 12),
+            ),
+            event(
+                ClaimEventKind::ClaimAndRepair,
+                12 + too_late,
+                Some(too_late - 1),
+                AddressInfo::new(12 + too_late, 12 + too_late, 12 + too_late, 12 + too_late),
+            ),
+        ];
+
+        let replay = Replay::run(&events);
+        assert_eq!(replay.mismatches.len(), 2, "{:?}", replay.mismatches);
+        assert!(replay
+            .mismatches
+            .iter()
+            .all(|mismatch| matches!(mismatch, ReplayMismatch::InvalidTransition { .. })));
+    }
+}
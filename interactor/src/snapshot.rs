@@ -0,0 +1,235 @@
+use crate::claim_events::AddressInfoRecord;
+use multiversx_sc_snippets::imports::*;
+use on_chain_claim::AddressInfo;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
+
+pub const SNAPSHOT_VERSION: u32 = 1;
+pub const ADDRESS_INFO_STORAGE_KEY: &[u8] = b"address_info";
+
+/// Versioned dump of the contract's `address_info` storage.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub contract_address: Bech32Address,
+    pub entries: Vec<SnapshotEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    pub address: Bech32Address,
+    pub current_streak: u64,
+    pub last_epoch_claimed: u64,
+    pub total_epochs_claimed: u64,
+    pub best_streak: u64,
+}
+
+impl SnapshotEntry {
+    pub fn new(address: Bech32Address, address_info: &AddressInfo) -> Self {
+        SnapshotEntry {
+            address,
+            current_streak: address_info.current_streak,
+            last_epoch_claimed: address_info.last_epoch_claimed,
+            total_epochs_claimed: address_info.total_epochs_claimed,
+            best_streak: address_info.best_streak,
+        }
+    }
+
+    pub fn address_info(&self) -> AddressInfoRecord {
+        AddressInfoRecord {
+            current_streak: self.current_streak,
+            last_epoch_claimed: self.last_epoch_claimed,
+            total_epochs_claimed: self.total_epochs_claimed,
+            best_streak: self.best_streak,
+        }
+    }
+}
+
+impl Snapshot {
+    pub fn new(contract_address: Bech32Address, entries: Vec<SnapshotEntry>) -> Self {
+        Snapshot {
+            version: SNAPSHOT_VERSION,
+            contract_address,
+            entries,
+        }
+    }
+
+    /// Builds a snapshot from the raw account storage, as returned by the gateway.
+    ///
+    /// Keys and values are hex encoded. Keys not belonging to the `address_info` mapper are ignored.
+    pub fn from_storage_pairs(
+        contract_address: Bech32Address,
+        pairs: &HashMap<String, String>,
+    ) -> Self {
+        let mut entries = BTreeMap::new();
+        for (key, value) in pairs {
+            let key = hex::decode(key).expect("invalid storage key hex");
+            let Some(address) = key.strip_prefix(ADDRESS_INFO_STORAGE_KEY) else {
+                continue;
+            };
+            if address.len() != 32 {
+                continue;
+            }
+
+            let value = hex::decode(value).expect("invalid storage value hex");
+            let address_info = AddressInfo::top_decode(value).expect("invalid address info");
+            let address = Bech32Address::from(Address::from_slice(address));
+
+            entries.insert(
+                address.to_bech32_string(),
+                SnapshotEntry::new(address, &address_info),
+            );
+        }
+
+        Snapshot::new(contract_address, entries.into_values().collect())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Self {
+        let content = std::fs::read_to_string(path).expect("failed to read snapshot file");
+        let snapshot: Snapshot = serde_json::from_str(&content).expect("invalid snapshot file");
+        assert_eq!(
+            snapshot.version, SNAPSHOT_VERSION,
+            "unsupported snapshot version"
+        );
+
+        snapshot
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) {
+        let content = serde_json::to_string_pretty(self).unwrap();
+        std::fs::write(path, content).expect("failed to write snapshot file");
+    }
+
+    /// Returns the entries that differ between two snapshots, regardless of the contract they were taken from.
+    pub fn diff<'a>(
+        &'a self,
+        other: &'a Snapshot,
+    ) -> Vec<(Option<&'a SnapshotEntry>, Option<&'a SnapshotEntry>)> {
+        let left = self.entries_by_address();
+        let right = other.entries_by_address();
+
+        let mut addresses: Vec<&String> = left.keys().chain(right.keys()).collect();
+        addresses.sort();
+        addresses.dedup();
+
+        addresses
+            .into_iter()
+            .map(|address| (left.get(address).copied(), right.get(address).copied()))
+            .filter(|(left, right)| left != right)
+            .collect()
+    }
+
+    fn entries_by_address(&self) -> BTreeMap<String, &SnapshotEntry> {
+        self.entries
+            .iter()
+            .map(|entry| (entry.address.to_bech32_string(), entry))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use multiversx_sc::codec::top_encode_to_vec_u8_or_panic;
+
+    fn address(byte: u8) -> Bech32Address {
+        Bech32Address::from(Address::from([byte; 32]))
+    }
+
+    fn entry(byte: u8, address_info: AddressInfo) -> SnapshotEntry {
+        SnapshotEntry::new(address(byte), &address_info)
+    }
+
+    fn snapshot(entries: Vec<SnapshotEntry>) -> Snapshot {
+        Snapshot::new(address(0), entries)
+    }
+
+    fn storage_pair(key: &[u8], address_info: &AddressInfo) -> (String, String) {
+        (
+            hex::encode(key),
+            hex::encode(top_encode_to_vec_u8_or_panic(address_info)),
+        )
+    }
+
+    fn address_info_key(byte: u8) -> Vec<u8> {
+        [ADDRESS_INFO_STORAGE_KEY, &[byte; 32]].concat()
+    }
+
+    #[test]
+    fn from_storage_pairs_decodes_address_infos() {
+        let pairs = HashMap::from([
+            storage_pair(&address_info_key(2), &AddressInfo::new(2, 5, 2, 2)),
+            storage_pair(&address_info_key(1), &AddressInfo::new(1, 4, 1, 1)),
+        ]);
+
+        let snapshot = Snapshot::from_storage_pairs(address(0), &pairs);
+        assert_eq!(snapshot.version, SNAPSHOT_VERSION);
+        assert_eq!(snapshot.contract_address, address(0));
+        // sorted by bech32 address, erd1qg... before erd1qy...
+        assert_eq!(
+            snapshot.entries,
+            [
+                entry(2, AddressInfo::new(2, 5, 2, 2)),
+                entry(1, AddressInfo::new(1, 4, 1, 1))
+            ]
+        );
+    }
+
+    #[test]
+    fn from_storage_pairs_skips_other_keys() {
+        let address_info = AddressInfo::new(1, 4, 1, 1);
+        let pairs = HashMap::from([
+            storage_pair(b"max_repair_gap", &address_info),
+            storage_pair(
+                &[b"claim_history".as_slice(), &[1; 32]].concat(),
+                &address_info,
+            ),
+            storage_pair(&address_info_key(1)[..40], &address_info),
+            storage_pair(&[address_info_key(1), vec![0]].concat(), &address_info),
+            storage_pair(&address_info_key(3), &address_info),
+        ]);
+
+        let snapshot = Snapshot::from_storage_pairs(address(0), &pairs);
+        assert_eq!(snapshot.entries, [entry(3, address_info)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid address info")]
+    fn from_storage_pairs_rejects_invalid_values() {
+        let pairs = HashMap::from([(hex::encode(address_info_key(1)), "01".to_string())]);
+
+        Snapshot::from_storage_pairs(address(0), &pairs);
+    }
+
+    #[test]
+    fn diff_of_equal_snapshots_is_empty() {
+        let left = snapshot(vec![entry(1, AddressInfo::new(1, 4, 1, 1))]);
+        let right = Snapshot::new(address(9), vec![entry(1, AddressInfo::new(1, 4, 1, 1))]);
+
+        assert!(left.diff(&right).is_empty());
+    }
+
+    #[test]
+    fn diff_lists_changed_missing_and_added_entries() {
+        let unchanged = entry(1, AddressInfo::new(1, 4, 1, 1));
+        let before = entry(2, AddressInfo::new(1, 4, 1, 1));
+        let after = entry(2, AddressInfo::new(2, 5, 2, 2));
+        let removed = entry(3, AddressInfo::new(1, 4, 1, 1));
+        let added = entry(4, AddressInfo::new(1, 4, 1, 1));
+        let left = snapshot(vec![removed.clone(), before.clone(), unchanged.clone()]);
+        let right = snapshot(vec![unchanged, added.clone(), after.clone()]);
+
+        // sorted by bech32 address, erd1qg... before erd1qs... before erd1qv...
+        assert_eq!(
+            left.diff(&right),
+            [
+                (Some(&before), Some(&after)),
+                (None, Some(&added)),
+                (Some(&removed), None)
+            ]
+        );
+    }
+}
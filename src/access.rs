@@ -0,0 +1,35 @@
+use multiversx_sc::imports::*;
+
+use crate::config;
+
+/// Epochs in which an active claimer last claimed: the current one or the previous one,
+/// so its streak can still be extended.
+pub const ACTIVE_CLAIMER_EPOCHS: u64 = 2;
+
+/// Streak checks for partner contracts gating their features, called synchronously from
+/// the same shard through `access_proxy`. Addresses that never claimed pass none of them.
+#[multiversx_sc::module]
+pub trait AccessModule: config::ConfigModule {
+    /// Whether the streak of the address is at least `min_streak` epochs and not broken by a missed epoch.
+    #[view(hasMinStreak)]
+    fn has_min_streak(&self, address: &ManagedAddress, min_streak: u64) -> bool {
+        let current_epoch = self.blockchain().get_block_epoch();
+
+        self.get_stored_address_info(address)
+            .is_some_and(|address_info| address_info.live_streak(current_epoch) >= min_streak)
+    }
+
+    /// Whether the address claimed in one of the last `epochs` epochs, the current one included.
+    #[view(hasClaimedInLastEpochs)]
+    fn has_claimed_in_last_epochs(&self, address: &ManagedAddress, epochs: u64) -> bool {
+        let current_epoch = self.blockchain().get_block_epoch();
+
+        self.get_stored_address_info(address)
+            .is_some_and(|address_info| address_info.claimed_in_last_epochs(current_epoch, epochs))
+    }
+
+    #[view(isActiveClaimer)]
+    fn is_active_claimer(&self, address: &ManagedAddress) -> bool {
+        self.has_claimed_in_last_epochs(address, ACTIVE_CLAIMER_EPOCHS)
+    }
+}
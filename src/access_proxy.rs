@@ -0,0 +1,384 @@
+// Code generated by the multiversx-sc proxy generator. DO NOT EDIT.
+
+////////////////////////////////////////////////////
+////////////////// AUTO-GENERATED //////////////////
+////////////////////////////////////////////////////
+
+#![allow(dead_code)]
+#![allow(clippy::all)]
+
+use multiversx_sc::proxy_imports::*;
+
+pub struct OnChainClaimContractProxy;
+
+impl<Env, From, To, Gas> TxProxyTrait<Env, From, To, Gas> for OnChainClaimContractProxy
+where
+    Env: TxEnv,
+    From: TxFrom<Env>,
+    To: TxTo<Env>,
+    Gas: TxGas<Env>,
+{
+    type TxProxyMethods = OnChainClaimContractProxyMethods<Env, From, To, Gas>;
+
+    fn proxy_methods(self, tx: Tx<Env, From, To, (), Gas, (), ()>) -> Self::TxProxyMethods {
+        OnChainClaimContractProxyMethods { wrapped_tx: tx }
+    }
+}
+
+pub struct OnChainClaimContractProxyMethods<Env, From, To, Gas>
+where
+    Env: TxEnv,
+    From: TxFrom<Env>,
+    To: TxTo<Env>,
+    Gas: TxGas<Env>,
+{
+    wrapped_tx: Tx<Env, From, To, (), Gas, (), ()>,
+}
+
+#[rustfmt::skip]
+impl<Env, From, To, Gas> OnChainClaimContractProxyMethods<Env, From, To, Gas>
+where
+    Env: TxEnv,
+    Env::Api: VMApi,
+    From: TxFrom<Env>,
+    To: TxTo<Env>,
+    Gas: TxGas<Env>,
+{
+    /// Whether the streak of the address is at least `min_streak` epochs and not broken by a missed epoch. 
+    pub fn has_min_streak<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+        Arg1: ProxyArg<u64>,
+    >(
+        self,
+        address: Arg0,
+        min_streak: Arg1,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, bool> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("hasMinStreak")
+            .argument(&address)
+            .argument(&min_streak)
+            .original_result()
+    }
+
+    /// Whether the address claimed in one of the last `epochs` epochs, the current one included. 
+    pub fn has_claimed_in_last_epochs<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+        Arg1: ProxyArg<u64>,
+    >(
+        self,
+        address: Arg0,
+        epochs: Arg1,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, bool> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("hasClaimedInLastEpochs")
+            .argument(&address)
+            .argument(&epochs)
+            .original_result()
+    }
+
+    pub fn is_active_claimer<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        address: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, bool> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("isActiveClaimer")
+            .argument(&address)
+            .original_result()
+    }
+}
+
+#[type_abi]
+#[derive(Default, NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct AddressInfo {
+    pub current_streak: u64,
+    pub last_epoch_claimed: u64,
+    pub total_epochs_claimed: u64,
+    pub best_streak: u64,
+}
+
+#[rustfmt::skip]
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub enum RepairPaymentSink<Api>
+where
+    Api: ManagedTypeApi,
+{
+    Burn,
+    Treasury {
+        treasury: ManagedAddress<Api>,
+    },
+    Split {
+        treasury: ManagedAddress<Api>,
+        burn_percentage: u64,
+    },
+}
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct AddressInfoUpdate {
+    pub previous: AddressInfo,
+    pub current: AddressInfo,
+}
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct ConfigChangeProposal<Api>
+where
+    Api: ManagedTypeApi,
+{
+    pub id: u64,
+    pub change: ConfigChange<Api>,
+    pub proposer: ManagedAddress<Api>,
+    pub proposed_epoch: u64,
+    pub executable_epoch: u64,
+}
+
+#[rustfmt::skip]
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub enum ConfigChange<Api>
+where
+    Api: ManagedTypeApi,
+{
+    SetRepairStreakPayment {
+        token_identifier: TokenIdentifier<Api>,
+        token_nonce: u64,
+    },
+    SetMaxRepairGap {
+        max_repair_gap: u64,
+    },
+    GrantRole {
+        role: Role,
+        address: ManagedAddress<Api>,
+    },
+    RevokeRole {
+        role: Role,
+        address: ManagedAddress<Api>,
+    },
+    SetTimelockEpochs {
+        timelock_epochs: u64,
+    },
+    SetRepairPaymentSink {
+        sink: RepairPaymentSink<Api>,
+    },
+    SetRepairPayment {
+        payment: EgldOrEsdtTokenPayment<Api>,
+    },
+}
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, Copy, PartialEq, Debug)]
+pub enum Role {
+    StateEditor,
+    PricingManager,
+    Pauser,
+    RoleManager,
+}
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct StateOverrideProposal<Api>
+where
+    Api: ManagedTypeApi,
+{
+    pub id: u64,
+    pub address: ManagedAddress<Api>,
+    pub address_info: AddressInfo,
+    pub proposer: ManagedAddress<Api>,
+    pub proposed_epoch: u64,
+    pub expiry_epoch: u64,
+}
+
+#[type_abi]
+#[derive(
+    NestedEncode,
+    NestedDecode,
+    TopEncode,
+    TopDecode,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Debug,
+)]
+pub enum BadgeTier {
+    Bronze,
+    Silver,
+    Gold,
+}
+
+#[rustfmt::skip]
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode)]
+pub enum AdminAction<Api>
+where
+    Api: ManagedTypeApi,
+{
+    UpdateState {
+        address: ManagedAddress<Api>,
+        previous: AddressInfo,
+        current: AddressInfo,
+    },
+    SetRepairStreakPayment {
+        previous: EgldOrEsdtTokenPayment<Api>,
+        current: EgldOrEsdtTokenPayment<Api>,
+    },
+    AddAdmin {
+        address: ManagedAddress<Api>,
+        was_admin: bool,
+    },
+    RemoveAdmin {
+        address: ManagedAddress<Api>,
+        was_admin: bool,
+    },
+    OpenSeason {
+        season: Season<Api>,
+    },
+    CloseSeason {
+        season_id: u64,
+        previous_end_epoch: u64,
+        end_epoch: u64,
+    },
+    AddAllowedContract {
+        address: ManagedAddress<Api>,
+        was_allowed: bool,
+    },
+    RemoveAllowedContract {
+        address: ManagedAddress<Api>,
+        was_allowed: bool,
+    },
+    GrantRole {
+        role: Role,
+        address: ManagedAddress<Api>,
+        had_role: bool,
+    },
+    RevokeRole {
+        role: Role,
+        address: ManagedAddress<Api>,
+        had_role: bool,
+    },
+    SetPaused {
+        paused: bool,
+        was_paused: bool,
+    },
+    SetMaxRepairGap {
+        previous: u64,
+        current: u64,
+    },
+    SetTimelockEpochs {
+        previous: u64,
+        current: u64,
+    },
+    SetStateOverrideQuorum {
+        previous_quorum: u32,
+        quorum: u32,
+        previous_expiry_epochs: u64,
+        expiry_epochs: u64,
+    },
+    SetClaimHistorySize {
+        previous: u32,
+        current: u32,
+    },
+    SetBadgeTiers {
+        previous: BadgeTiers,
+        current: BadgeTiers,
+    },
+    SetReferralMilestones {
+        previous: ManagedVec<Api, ReferralMilestone>,
+        current: ManagedVec<Api, ReferralMilestone>,
+    },
+    SetRepairPaymentSink {
+        previous: RepairPaymentSink<Api>,
+        current: RepairPaymentSink<Api>,
+    },
+    ApproveStateOverride {
+        id: u64,
+    },
+    CancelStateOverride {
+        proposal: StateOverrideProposal<Api>,
+    },
+    CancelConfigChange {
+        proposal: ConfigChangeProposal<Api>,
+    },
+}
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct Season<Api>
+where
+    Api: ManagedTypeApi,
+{
+    pub id: u64,
+    pub name: ManagedBuffer<Api>,
+    pub start_epoch: u64,
+    pub end_epoch: u64,
+}
+
+#[type_abi]
+#[derive(Default, NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct BadgeTiers {
+    pub bronze: u64,
+    pub silver: u64,
+    pub gold: u64,
+}
+
+#[type_abi]
+#[derive(
+    ManagedVecItem, NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug,
+)]
+pub struct ReferralMilestone {
+    pub streak: u64,
+    pub points: u64,
+}
+
+#[type_abi]
+#[derive(Default, NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct SeasonStats {
+    pub season_id: u64,
+    pub current_streak: u64,
+    pub last_epoch_claimed: u64,
+    pub epochs_claimed: u64,
+    pub best_streak: u64,
+}
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct ClaimHistoryEntry {
+    pub epoch: u64,
+    pub kind: ClaimHistoryKind,
+}
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, Copy, PartialEq, Debug)]
+pub enum ClaimHistoryKind {
+    Claim,
+    Repair,
+    AdminSet,
+}
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct ClaimBitmapWord {
+    pub first_epoch: u64,
+    pub claimed: u64,
+    pub repaired: u64,
+}
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct BadgeAttributes<Api>
+where
+    Api: ManagedTypeApi,
+{
+    pub owner: ManagedAddress<Api>,
+    pub tier: BadgeTier,
+    pub best_streak: u64,
+    pub tier_epoch: u64,
+}
@@ -1,7 +1,7 @@
 use multiversx_sc::derive_imports::*;
 
 #[type_abi]
-#[derive(Default, NestedEncode, NestedDecode, TopEncode, TopDecode)]
+#[derive(Default, NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
 pub struct AddressInfo {
     pub current_streak: u64,
     pub last_epoch_claimed: u64,
@@ -32,4 +32,150 @@ impl AddressInfo {
             best_streak: 1,
         }
     }
+
+    /// Epochs between the last claimed one and `current_epoch`, both excluded.
+    pub fn missed_epochs(&self, current_epoch: u64) -> u64 {
+        if current_epoch <= self.last_epoch_claimed {
+            return 0;
+        }
+
+        current_epoch - self.last_epoch_claimed - 1
+    }
+
+    /// The current streak while it can still be extended by a claim, 0 once an epoch was missed.
+    pub fn live_streak(&self, current_epoch: u64) -> u64 {
+        if self.missed_epochs(current_epoch) > 0 {
+            return 0;
+        }
+
+        self.current_streak
+    }
+
+    /// Whether one of the last `epochs` epochs, `current_epoch` included, was claimed.
+    pub fn claimed_in_last_epochs(&self, current_epoch: u64, epochs: u64) -> bool {
+        current_epoch.saturating_sub(self.last_epoch_claimed) < epochs
+    }
+
+    pub fn can_be_repaired(&self, current_epoch: u64, max_missed_epochs: u64) -> bool {
+        let missed_epochs = self.missed_epochs(current_epoch);
+
+        missed_epochs > 0 && missed_epochs <= max_missed_epochs
+    }
+
+    /// Claims `current_epoch` for an address that might not have claimed before.
+    pub fn claim(
+        address_info: Option<&AddressInfo>,
+        current_epoch: u64,
+    ) -> Result<(AddressInfo, ClaimOutcome), StreakError> {
+        let Some(address_info) = address_info else {
+            return Ok((
+                AddressInfo::new_with_epoch(current_epoch),
+                ClaimOutcome::Started,
+            ));
+        };
+
+        let mut address_info = address_info.clone();
+        let outcome = address_info.apply_claim(current_epoch)?;
+
+        Ok((address_info, outcome))
+    }
+
+    /// Repairs the streak of an address that might not have claimed before, returning the number of repaired epochs.
+    pub fn claim_and_repair(
+        address_info: Option<&AddressInfo>,
+        current_epoch: u64,
+        max_missed_epochs: u64,
+    ) -> Result<(AddressInfo, u64), StreakError> {
+        let Some(address_info) = address_info else {
+            return Err(StreakError::NothingToRepair);
+        };
+
+        let mut address_info = address_info.clone();
+        let missed_epochs = address_info.apply_repair(current_epoch, max_missed_epochs)?;
+
+        Ok((address_info, missed_epochs))
+    }
+
+    /// Claims `current_epoch`, extending the streak if the previous epoch was claimed, restarting it otherwise.
+    ///
+    /// Leaves `self` untouched on error. Counters saturate at `u64::MAX`, since `updateState` accepts any value.
+    pub fn apply_claim(&mut self, current_epoch: u64) -> Result<ClaimOutcome, StreakError> {
+        if self.last_epoch_claimed >= current_epoch {
+            return Err(StreakError::EpochAlreadyClaimed);
+        }
+
+        // last_epoch_claimed < current_epoch, so the increment can't overflow
+        let outcome = if self.last_epoch_claimed + 1 == current_epoch {
+            self.current_streak = self.current_streak.saturating_add(1);
+            ClaimOutcome::Extended
+        } else {
+            self.current_streak = 1;
+            ClaimOutcome::Reset
+        };
+
+        self.total_epochs_claimed = self.total_epochs_claimed.saturating_add(1);
+        self.last_epoch_claimed = current_epoch;
+        self.update_best_streak();
+
+        Ok(outcome)
+    }
+
+    /// Claims `current_epoch` along with the missed epochs, as long as there are at most `max_missed_epochs` of them.
+    /// Returns the number of repaired epochs.
+    ///
+    /// Leaves `self` untouched on error. Counters saturate at `u64::MAX`, since `updateState` accepts any value.
+    pub fn apply_repair(
+        &mut self,
+        current_epoch: u64,
+        max_missed_epochs: u64,
+    ) -> Result<u64, StreakError> {
+        if !self.can_be_repaired(current_epoch, max_missed_epochs) {
+            return Err(StreakError::RepairNotAllowed);
+        }
+
+        let missed_epochs = self.missed_epochs(current_epoch);
+        // missed_epochs < current_epoch, so the increment itself can't overflow
+        self.current_streak = self.current_streak.saturating_add(missed_epochs + 1);
+        self.total_epochs_claimed = self.total_epochs_claimed.saturating_add(missed_epochs + 1);
+        self.last_epoch_claimed = current_epoch;
+        self.update_best_streak();
+
+        Ok(missed_epochs)
+    }
+
+    fn update_best_streak(&mut self) {
+        if self.best_streak < self.current_streak {
+            self.best_streak = self.current_streak;
+        }
+    }
+}
+
+/// Reason for which a streak transition is rejected.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StreakError {
+    EpochAlreadyClaimed,
+    NothingToRepair,
+    RepairNotAllowed,
+}
+
+impl StreakError {
+    /// The error message the contract fails with.
+    pub fn message(&self) -> &'static str {
+        match self {
+            StreakError::EpochAlreadyClaimed => "epoch already claimed",
+            StreakError::NothingToRepair => "can't repair streak for address",
+            StreakError::RepairNotAllowed => "can't repair streak for current epoch",
+        }
+    }
+}
+
+/// What a claim did to the current streak.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ClaimOutcome {
+    /// First claim of the address.
+    Started,
+    /// The previous epoch was claimed, the streak goes on.
+    Extended,
+    /// At least one epoch was missed, the streak starts over.
+    Reset,
 }
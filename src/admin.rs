@@ -0,0 +1,136 @@
+use multiversx_sc::{derive_imports::*, imports::*};
+
+use crate::events::{self, AdminAction};
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, Copy, PartialEq, Debug)]
+pub enum Role {
+    /// Writes address infos with `updateState` and schedules seasons.
+    StateEditor,
+    /// Proposes repair payment and repair gap changes.
+    PricingManager,
+    /// Pauses and unpauses claims.
+    Pauser,
+    /// Proposes role and timelock changes, and allows smart contracts to claim.
+    RoleManager,
+}
+
+impl Role {
+    pub const ALL: [Role; 4] = [
+        Role::StateEditor,
+        Role::PricingManager,
+        Role::Pauser,
+        Role::RoleManager,
+    ];
+}
+
+/// Roles granted to addresses, each restricting a set of endpoints, with every admin action being audited.
+///
+/// Roles are granted and revoked through timelocked config changes.
+/// The owner can always propose them, so that access can't be lost.
+#[multiversx_sc::module]
+pub trait AdminModule: events::EventsModule {
+    #[endpoint(pause)]
+    fn pause(&self) {
+        self.require_caller_has_role(Role::Pauser);
+
+        self.set_paused(true);
+    }
+
+    #[endpoint(unpause)]
+    fn unpause(&self) {
+        self.require_caller_has_role(Role::Pauser);
+
+        self.set_paused(false);
+    }
+
+    fn set_paused(&self, paused: bool) {
+        let was_paused = self.paused().replace(paused);
+
+        self.record_admin_action(AdminAction::SetPaused { paused, was_paused });
+    }
+
+    fn require_not_paused(&self) {
+        require!(!self.paused().get(), "Claims are paused");
+    }
+
+    fn internal_grant_role(&self, role: Role, address: ManagedAddress) {
+        let had_role = !self.role_holders(role).insert(address.clone());
+
+        self.record_admin_action(AdminAction::GrantRole {
+            role,
+            address,
+            had_role,
+        });
+    }
+
+    fn internal_revoke_role(&self, role: Role, address: ManagedAddress) {
+        let had_role = self.role_holders(role).swap_remove(&address);
+
+        self.record_admin_action(AdminAction::RevokeRole {
+            role,
+            address,
+            had_role,
+        });
+    }
+
+    /// Gives every role to the admins of the flat admin list used before roles, then clears it.
+    fn migrate_legacy_admins(&self) {
+        let legacy_admins = self.legacy_admins().iter().collect::<ManagedVec<_>>();
+        for admin in legacy_admins.iter() {
+            for role in Role::ALL {
+                self.internal_grant_role(role, admin.clone_value());
+            }
+        }
+
+        self.legacy_admins().clear();
+    }
+
+    fn require_caller_has_role(&self, role: Role) {
+        require!(
+            self.has_role(role, self.blockchain().get_caller()),
+            "Caller doesn't have the required role"
+        );
+    }
+
+    fn require_caller_can_manage_roles(&self) {
+        let caller = self.blockchain().get_caller();
+        require!(
+            caller == self.blockchain().get_owner_address()
+                || self.has_role(Role::RoleManager, caller),
+            "Caller doesn't have the required role"
+        );
+    }
+
+    fn record_admin_action(&self, action: AdminAction<Self::Api>) {
+        let action_id = self.admin_action_count().update(|count| {
+            *count += 1;
+            *count
+        });
+
+        let caller = self.blockchain().get_caller();
+        let current_epoch = self.blockchain().get_block_epoch();
+        self.admin_action_event(action_id, &caller, current_epoch, &action);
+    }
+
+    #[view(hasRole)]
+    fn has_role(&self, role: Role, address: ManagedAddress) -> bool {
+        self.role_holders(role).contains(&address)
+    }
+
+    #[view(getRoleHolders)]
+    #[storage_mapper("role_holders")]
+    fn role_holders(&self, role: Role) -> UnorderedSetMapper<ManagedAddress>;
+
+    #[view(isPaused)]
+    #[storage_mapper("paused")]
+    fn paused(&self) -> SingleValueMapper<bool>;
+
+    /// Admin list of `multiversx_sc_modules::only_admin`, only read to migrate to roles.
+    #[storage_mapper("only_admin_module:admins")]
+    fn legacy_admins(&self) -> UnorderedSetMapper<ManagedAddress>;
+
+    #[view(getAdminActionCount)]
+    #[storage_mapper("admin_action_count")]
+    fn admin_action_count(&self) -> SingleValueMapper<u64>;
+}
@@ -0,0 +1,192 @@
+use multiversx_sc::{derive_imports::*, imports::*};
+
+use crate::{
+    address_info::AddressInfo,
+    admin::{self, Role},
+    events::{self, AdminAction},
+};
+
+#[type_abi]
+#[derive(
+    NestedEncode,
+    NestedDecode,
+    TopEncode,
+    TopDecode,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Debug,
+)]
+pub enum BadgeTier {
+    Bronze,
+    Silver,
+    Gold,
+}
+
+/// Minimum `best_streak` of each tier. All zero until set, in which case no badge is awarded.
+#[type_abi]
+#[derive(Default, NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct BadgeTiers {
+    pub bronze: u64,
+    pub silver: u64,
+    pub gold: u64,
+}
+
+impl BadgeTiers {
+    pub fn is_valid(&self) -> bool {
+        0 < self.bronze && self.bronze < self.silver && self.silver < self.gold
+    }
+
+    pub fn tier_for(&self, best_streak: u64) -> Option<BadgeTier> {
+        if !self.is_valid() {
+            return None;
+        }
+
+        if best_streak >= self.gold {
+            Some(BadgeTier::Gold)
+        } else if best_streak >= self.silver {
+            Some(BadgeTier::Silver)
+        } else if best_streak >= self.bronze {
+            Some(BadgeTier::Bronze)
+        } else {
+            None
+        }
+    }
+}
+
+/// Attributes of a badge NFT, set when it is minted and on each tier-up.
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct BadgeAttributes<M: ManagedTypeApi> {
+    pub owner: ManagedAddress<M>,
+    pub tier: BadgeTier,
+    /// `best_streak` of the owner when the tier was reached.
+    pub best_streak: u64,
+    pub tier_epoch: u64,
+}
+
+/// Streak badges, one NFT per address from the collection issued by the contract.
+///
+/// Badges are soulbound: the contract keeps them, bound to their owner through `badge_nonce`
+/// and the `owner` attribute, since attributes can only be updated by the holder of the NFT.
+/// On tier-up the attributes of the same NFT are updated instead of minting another one.
+#[multiversx_sc::module]
+pub trait BadgeModule: events::EventsModule + admin::AdminModule {
+    /// Issues the collection and gives the contract all its roles, paid with the issue cost in EGLD.
+    #[only_owner]
+    #[payable("EGLD")]
+    #[endpoint(issueBadgeCollection)]
+    fn issue_badge_collection(
+        &self,
+        token_display_name: ManagedBuffer,
+        token_ticker: ManagedBuffer,
+    ) {
+        require!(
+            self.badge_token().is_empty(),
+            "Badge collection already issued"
+        );
+
+        let issue_cost = self.call_value().egld_value().clone_value();
+        self.badge_token().issue_and_set_all_roles(
+            EsdtTokenType::NonFungible,
+            issue_cost,
+            token_display_name,
+            token_ticker,
+            0,
+            None,
+        );
+    }
+
+    #[endpoint(setBadgeTiers)]
+    fn set_badge_tiers(&self, bronze: u64, silver: u64, gold: u64) {
+        self.require_caller_has_role(Role::StateEditor);
+        let tiers = BadgeTiers {
+            bronze,
+            silver,
+            gold,
+        };
+        require!(tiers.is_valid(), "Invalid badge tiers");
+
+        let previous = self.get_badge_tiers();
+        self.badge_tiers().set(&tiers);
+
+        self.record_admin_action(AdminAction::SetBadgeTiers {
+            previous,
+            current: tiers,
+        });
+    }
+
+    /// Mints the badge of the first tier reached by `address`, or upgrades it to a higher tier.
+    /// Badges are never downgraded, even when `best_streak` is lowered by a state override.
+    fn award_badge(&self, address: &ManagedAddress, address_info: &AddressInfo) {
+        if !self.badge_token().get_token_state().is_set() {
+            return;
+        }
+        let Some(tier) = self.get_badge_tiers().tier_for(address_info.best_streak) else {
+            return;
+        };
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        let attributes = BadgeAttributes {
+            owner: address.clone(),
+            tier,
+            best_streak: address_info.best_streak,
+            tier_epoch: current_epoch,
+        };
+
+        let nonce_mapper = self.badge_nonce(address);
+        if nonce_mapper.is_empty() {
+            let badge = self
+                .badge_token()
+                .nft_create(BigUint::from(1u64), &attributes);
+            nonce_mapper.set(badge.token_nonce);
+            self.badge_minted_event(address, badge.token_nonce, tier, current_epoch);
+            return;
+        }
+
+        let nonce = nonce_mapper.get();
+        let previous_attributes: BadgeAttributes<Self::Api> =
+            self.badge_token().get_token_attributes(nonce);
+        if tier <= previous_attributes.tier {
+            return;
+        }
+
+        self.badge_token().nft_update_attributes(nonce, &attributes);
+        self.badge_upgraded_event(address, nonce, tier, current_epoch);
+    }
+
+    #[view(getBadge)]
+    fn get_badge(&self, address: &ManagedAddress) -> OptionalValue<BadgeAttributes<Self::Api>> {
+        let nonce_mapper = self.badge_nonce(address);
+        if nonce_mapper.is_empty() {
+            return OptionalValue::None;
+        }
+
+        OptionalValue::Some(self.badge_token().get_token_attributes(nonce_mapper.get()))
+    }
+
+    #[view(getBadgeTiers)]
+    fn get_badge_tiers(&self) -> BadgeTiers {
+        let tiers_mapper = self.badge_tiers();
+        if tiers_mapper.is_empty() {
+            return BadgeTiers::default();
+        }
+
+        tiers_mapper.get()
+    }
+
+    #[view(getBadgeTokenId)]
+    #[storage_mapper("badge_token")]
+    fn badge_token(&self) -> NonFungibleTokenMapper;
+
+    #[storage_mapper("badge_tiers")]
+    fn badge_tiers(&self) -> SingleValueMapper<BadgeTiers>;
+
+    /// Nonce of the badge of an address, 0 when it has none.
+    #[view(getBadgeNonce)]
+    #[storage_mapper("badge_nonce")]
+    fn badge_nonce(&self, address: &ManagedAddress) -> SingleValueMapper<u64>;
+}
@@ -0,0 +1,96 @@
+use multiversx_sc::{derive_imports::*, imports::*};
+
+pub const EPOCHS_PER_WORD: u64 = 64;
+/// Words returned at most by `getClaimBitmap`, about 11 years of epochs.
+pub const MAX_BITMAP_WORDS: u64 = 64;
+
+/// Epochs `first_epoch..first_epoch + EPOCHS_PER_WORD`, bit `i` standing for `first_epoch + i`.
+/// Repaired epochs are only marked in `repaired`.
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct ClaimBitmapWord {
+    pub first_epoch: u64,
+    pub claimed: u64,
+    pub repaired: u64,
+}
+
+impl ClaimBitmapWord {
+    /// Clears the bits of the epochs outside `start_epoch..=end_epoch`.
+    pub fn masked(mut self, start_epoch: u64, end_epoch: u64) -> Self {
+        let mut mask = u64::MAX;
+        if start_epoch > self.first_epoch {
+            mask &= u64::MAX << (start_epoch - self.first_epoch);
+        }
+        let last_epoch = self.first_epoch + EPOCHS_PER_WORD - 1;
+        if end_epoch < last_epoch {
+            mask &= u64::MAX >> (last_epoch - end_epoch);
+        }
+
+        self.claimed &= mask;
+        self.repaired &= mask;
+        self
+    }
+}
+
+/// Which epochs each address claimed or repaired, for calendar views.
+/// Epochs set through `updateState` are not marked, only the ones actually claimed.
+#[multiversx_sc::module]
+pub trait ClaimBitmapModule {
+    fn mark_claimed_epoch(&self, address: &ManagedAddress, epoch: u64) {
+        let (claimed, repaired) = self.get_claim_bitmap_bits(address, epoch / EPOCHS_PER_WORD);
+        self.claim_bitmap_bits(address, epoch / EPOCHS_PER_WORD)
+            .set((claimed | 1 << (epoch % EPOCHS_PER_WORD), repaired));
+    }
+
+    fn mark_repaired_epoch(&self, address: &ManagedAddress, epoch: u64) {
+        let (claimed, repaired) = self.get_claim_bitmap_bits(address, epoch / EPOCHS_PER_WORD);
+        self.claim_bitmap_bits(address, epoch / EPOCHS_PER_WORD)
+            .set((claimed, repaired | 1 << (epoch % EPOCHS_PER_WORD)));
+    }
+
+    fn get_claim_bitmap_bits(&self, address: &ManagedAddress, word_index: u64) -> (u64, u64) {
+        let bits_mapper = self.claim_bitmap_bits(address, word_index);
+        if bits_mapper.is_empty() {
+            return (0, 0);
+        }
+
+        bits_mapper.get()
+    }
+
+    /// The words covering `start_epoch..=end_epoch`, with the epochs out of the range cleared.
+    #[view(getClaimBitmap)]
+    fn get_claim_bitmap(
+        &self,
+        address: &ManagedAddress,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> MultiValueEncoded<ClaimBitmapWord> {
+        require!(start_epoch <= end_epoch, "Invalid epoch range");
+        let first_word = start_epoch / EPOCHS_PER_WORD;
+        let last_word = end_epoch / EPOCHS_PER_WORD;
+        require!(
+            last_word - first_word < MAX_BITMAP_WORDS,
+            "Epoch range too large"
+        );
+
+        (first_word..=last_word)
+            .map(|word_index| {
+                let (claimed, repaired) = self.get_claim_bitmap_bits(address, word_index);
+                ClaimBitmapWord {
+                    first_epoch: word_index * EPOCHS_PER_WORD,
+                    claimed,
+                    repaired,
+                }
+                .masked(start_epoch, end_epoch)
+            })
+            .collect()
+    }
+
+    /// Claimed and repaired bits of the word covering the epochs from `word_index * EPOCHS_PER_WORD`.
+    #[storage_mapper("claim_bitmap_bits")]
+    fn claim_bitmap_bits(
+        &self,
+        address: &ManagedAddress,
+        word_index: u64,
+    ) -> SingleValueMapper<(u64, u64)>;
+}
@@ -0,0 +1,117 @@
+use multiversx_sc::{derive_imports::*, imports::*};
+
+use crate::{
+    admin::{self, Role},
+    events::{self, AdminAction},
+};
+
+/// Entries kept per address, until another size is set by the state editors.
+pub const DEFAULT_CLAIM_HISTORY_SIZE: u32 = 30;
+pub const MAX_CLAIM_HISTORY_SIZE: u32 = 100;
+/// Entries out of the window evicted by each new entry, so that a reduced size is caught up on
+/// over the next entries of the address instead of being paid for by a single claim.
+pub const MAX_EVICTIONS_PER_ENTRY: u64 = 2;
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, Copy, PartialEq, Debug)]
+pub enum ClaimHistoryKind {
+    Claim,
+    /// A missed epoch, repaired by `claimAndRepair`.
+    Repair,
+    /// The address info was set through `updateState`, in the epoch of the entry.
+    AdminSet,
+}
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct ClaimHistoryEntry {
+    pub epoch: u64,
+    pub kind: ClaimHistoryKind,
+}
+
+/// The last entries of each address, oldest ones being evicted once the history is full.
+///
+/// Entries are stored by sequence number, the history of an address being the range
+/// `claim_history_start..claim_history_end`, so a size change never mixes up entries.
+#[multiversx_sc::module]
+pub trait ClaimHistoryModule: events::EventsModule + admin::AdminModule {
+    #[endpoint(setClaimHistorySize)]
+    fn set_claim_history_size(&self, size: u32) {
+        self.require_caller_has_role(Role::StateEditor);
+        require!(
+            size > 0 && size <= MAX_CLAIM_HISTORY_SIZE,
+            "Invalid claim history size"
+        );
+
+        let previous = self.get_claim_history_size();
+        self.claim_history_size().set(size);
+
+        self.record_admin_action(AdminAction::SetClaimHistorySize {
+            previous,
+            current: size,
+        });
+    }
+
+    fn record_claim_history(&self, address: &ManagedAddress, epoch: u64, kind: ClaimHistoryKind) {
+        let end = self.claim_history_end(address).update(|end| {
+            *end += 1;
+            *end
+        });
+        self.claim_history_entry(address, end - 1)
+            .set(ClaimHistoryEntry { epoch, kind });
+
+        // Evicts more than one entry only after the size was reduced, the view skipping the ones left out of the window
+        let size = self.get_claim_history_size() as u64;
+        let start_mapper = self.claim_history_start(address);
+        let mut start = start_mapper.get();
+        let eviction_end = end
+            .saturating_sub(size)
+            .min(start + MAX_EVICTIONS_PER_ENTRY);
+        while start < eviction_end {
+            self.claim_history_entry(address, start).clear();
+            start += 1;
+        }
+        start_mapper.set(start);
+    }
+
+    /// The entries of an address, oldest first.
+    #[view(getClaimHistory)]
+    fn get_claim_history(&self, address: &ManagedAddress) -> MultiValueEncoded<ClaimHistoryEntry> {
+        let end = self.claim_history_end(address).get();
+        let size = self.get_claim_history_size() as u64;
+        let start = self
+            .claim_history_start(address)
+            .get()
+            .max(end.saturating_sub(size));
+
+        (start..end)
+            .map(|index| self.claim_history_entry(address, index).get())
+            .collect()
+    }
+
+    #[view(getClaimHistorySize)]
+    fn get_claim_history_size(&self) -> u32 {
+        let size_mapper = self.claim_history_size();
+        if size_mapper.is_empty() {
+            return DEFAULT_CLAIM_HISTORY_SIZE;
+        }
+
+        size_mapper.get()
+    }
+
+    #[storage_mapper("claim_history_size")]
+    fn claim_history_size(&self) -> SingleValueMapper<u32>;
+
+    #[storage_mapper("claim_history_start")]
+    fn claim_history_start(&self, address: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("claim_history_end")]
+    fn claim_history_end(&self, address: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("claim_history_entry")]
+    fn claim_history_entry(
+        &self,
+        address: &ManagedAddress,
+        index: u64,
+    ) -> SingleValueMapper<ClaimHistoryEntry>;
+}
@@ -1,8 +1,51 @@
-use multiversx_sc::imports::*;
+use multiversx_sc::{derive_imports::*, imports::*};
 
 use crate::address_info::*;
 
+/// Default repair gap, until another one is set through a config change.
 pub const MAX_REPAIR_GAP: u64 = 5;
+// Allow MAX_REPAIR_GAP + 1 in order to not have failed transaction when the user sends the claimAndRepair transaction
+// in the last round of the allowed epoch. From UI, we allow MAX_REPAIR_GAP = 5 (using canBeRepaired view)
+pub const MAX_REPAIR_GAP_ON_CLAIM: u64 = MAX_REPAIR_GAP + 1;
+/// Highest repair gap a config change can set, each repaired epoch being written to the claim history and bitmap.
+pub const MAX_ALLOWED_REPAIR_GAP: u64 = 30;
+
+/// Where the payments of `claimAndRepair` go, burn until another sink is set through a config change.
+/// EGLD payments can't be burnt, they all go to the treasury.
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub enum RepairPaymentSink<M: ManagedTypeApi> {
+    Burn,
+    Treasury {
+        treasury: ManagedAddress<M>,
+    },
+    /// Burns `burn_percentage` of the payment, rounded down, and forwards the rest to the treasury.
+    Split {
+        treasury: ManagedAddress<M>,
+        burn_percentage: u64,
+    },
+}
+
+impl<M: ManagedTypeApi> RepairPaymentSink<M> {
+    pub fn treasury(&self) -> Option<&ManagedAddress<M>> {
+        match self {
+            RepairPaymentSink::Burn => None,
+            RepairPaymentSink::Treasury { treasury }
+            | RepairPaymentSink::Split { treasury, .. } => Some(treasury),
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        match self {
+            RepairPaymentSink::Burn => true,
+            RepairPaymentSink::Treasury { treasury } => !treasury.is_zero(),
+            RepairPaymentSink::Split {
+                treasury,
+                burn_percentage,
+            } => !treasury.is_zero() && *burn_percentage <= 100,
+        }
+    }
+}
 
 #[multiversx_sc::module]
 pub trait ConfigModule {
@@ -14,14 +57,6 @@ pub trait ConfigModule {
         require!(address_shard == sc_shard, "wrong shard");
     }
 
-    fn get_missed_epochs(&self, current_epoch: u64, last_epoch_claimed: u64) -> u64 {
-        if current_epoch <= last_epoch_claimed {
-            return 0;
-        }
-
-        current_epoch - last_epoch_claimed - 1
-    }
-
     #[view(getAddressInfo)]
     fn get_address_info(&self, address: &ManagedAddress) -> AddressInfo {
         let address_info_mapper = self.address_info(address);
@@ -33,6 +68,15 @@ pub trait ConfigModule {
         address_info_mapper.get()
     }
 
+    fn get_stored_address_info(&self, address: &ManagedAddress) -> Option<AddressInfo> {
+        let address_info_mapper = self.address_info(address);
+        if address_info_mapper.is_empty() {
+            return None;
+        }
+
+        Some(address_info_mapper.get())
+    }
+
     #[view(canBeRepaired)]
     fn can_be_repaired(&self, address: &ManagedAddress) -> bool {
         let address_info_mapper = self.address_info(address);
@@ -40,17 +84,136 @@ pub trait ConfigModule {
             return false;
         }
 
-        let address_info = address_info_mapper.get();
         let current_epoch = self.blockchain().get_block_epoch();
-        let missed_epochs = self.get_missed_epochs(current_epoch, address_info.last_epoch_claimed);
 
-        missed_epochs > 0 && missed_epochs <= MAX_REPAIR_GAP
+        address_info_mapper
+            .get()
+            .can_be_repaired(current_epoch, self.get_max_repair_gap())
+    }
+
+    #[view(getMaxRepairGap)]
+    fn get_max_repair_gap(&self) -> u64 {
+        let max_repair_gap_mapper = self.max_repair_gap();
+        if max_repair_gap_mapper.is_empty() {
+            return MAX_REPAIR_GAP;
+        }
+
+        max_repair_gap_mapper.get()
+    }
+
+    /// Same margin as `MAX_REPAIR_GAP_ON_CLAIM`, over the configured repair gap.
+    fn get_max_repair_gap_on_claim(&self) -> u64 {
+        self.get_max_repair_gap().saturating_add(1)
+    }
+
+    fn require_valid_max_repair_gap(&self, max_repair_gap: u64) {
+        require!(
+            max_repair_gap > 0 && max_repair_gap <= MAX_ALLOWED_REPAIR_GAP,
+            "Invalid repair gap"
+        );
+    }
+
+    fn internal_set_repair_streak_payment(
+        &self,
+        repair_streak_token_identifier: TokenIdentifier,
+        repair_streak_token_nonce: u64,
+    ) {
+        require!(
+            repair_streak_token_identifier.is_valid_esdt_identifier(),
+            "Invalid token ID",
+        );
+
+        let payment = EgldOrEsdtTokenPayment::new(
+            EgldOrEsdtTokenIdentifier::esdt(repair_streak_token_identifier),
+            repair_streak_token_nonce,
+            BigUint::from(1u64),
+        );
+        self.internal_set_repair_payment(payment);
+    }
+
+    fn internal_set_repair_payment(&self, payment: EgldOrEsdtTokenPayment) {
+        self.require_valid_repair_payment(&payment);
+        self.require_sink_accepts(&self.get_repair_payment_sink(), &payment.token_identifier);
+
+        self.repair_streak_payment().set(payment);
+    }
+
+    fn require_valid_repair_payment(&self, payment: &EgldOrEsdtTokenPayment) {
+        require!(payment.token_identifier.is_valid(), "Invalid token ID");
+        require!(
+            payment.token_identifier.is_esdt() || payment.token_nonce == 0,
+            "Invalid token nonce"
+        );
+        require!(payment.amount > 0, "Invalid repair payment amount");
+    }
+
+    fn require_sink_accepts(
+        &self,
+        sink: &RepairPaymentSink<Self::Api>,
+        token_identifier: &EgldOrEsdtTokenIdentifier,
+    ) {
+        require!(
+            token_identifier.is_esdt() || sink.treasury().is_some(),
+            "EGLD repair payments need a treasury"
+        );
+    }
+
+    #[view(getRepairPaymentSink)]
+    fn get_repair_payment_sink(&self) -> RepairPaymentSink<Self::Api> {
+        let sink_mapper = self.repair_payment_sink();
+        if sink_mapper.is_empty() {
+            return RepairPaymentSink::Burn;
+        }
+
+        sink_mapper.get()
+    }
+
+    /// Burns the payment of a repair or forwards it to the treasury, depending on the sink.
+    fn sink_repair_payment(&self, payment: &EgldOrEsdtTokenPayment) {
+        let sink = self.get_repair_payment_sink();
+        if payment.token_identifier.is_egld() {
+            let Some(treasury) = sink.treasury() else {
+                sc_panic!("EGLD repair payments need a treasury");
+            };
+            self.tx().to(treasury).egld(&payment.amount).transfer();
+            return;
+        }
+
+        let token_identifier = payment.token_identifier.clone().unwrap_esdt();
+        let burn_amount = match &sink {
+            RepairPaymentSink::Burn => payment.amount.clone(),
+            RepairPaymentSink::Treasury { .. } => BigUint::zero(),
+            RepairPaymentSink::Split {
+                burn_percentage, ..
+            } => &payment.amount * *burn_percentage / 100u64,
+        };
+
+        if burn_amount > 0 {
+            self.send()
+                .esdt_local_burn(&token_identifier, payment.token_nonce, &burn_amount);
+        }
+        let treasury_amount = &payment.amount - &burn_amount;
+        if let Some(treasury) = sink.treasury() {
+            if treasury_amount > 0 {
+                self.tx()
+                    .to(treasury)
+                    .single_esdt(&token_identifier, payment.token_nonce, &treasury_amount)
+                    .transfer();
+            }
+        }
     }
 
     #[storage_mapper("address_info")]
     fn address_info(&self, address: &ManagedAddress) -> SingleValueMapper<AddressInfo>;
 
+    #[storage_mapper("max_repair_gap")]
+    fn max_repair_gap(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("repair_payment_sink")]
+    fn repair_payment_sink(&self) -> SingleValueMapper<RepairPaymentSink<Self::Api>>;
+
+    /// Same encoding as the `EsdtTokenPayment` stored before EGLD payments, for ESDTs.
     #[view(getRepairStreakPayment)]
     #[storage_mapper("repair_streak_payment")]
-    fn repair_streak_payment(&self) -> SingleValueMapper<EsdtTokenPayment>;
+    fn repair_streak_payment(&self) -> SingleValueMapper<EgldOrEsdtTokenPayment>;
 }
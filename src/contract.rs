@@ -1,124 +1,192 @@
 #![no_std]
 #![allow(unused_attributes)]
 
-pub use address_info::AddressInfo;
+pub use address_info::{AddressInfo, ClaimOutcome, StreakError};
 use multiversx_sc::imports::*;
+use multiversx_sc_modules::default_issue_callbacks;
 
+pub mod access;
+pub mod access_proxy;
 pub mod address_info;
+pub mod admin;
+pub mod badge;
+pub mod claim_bitmap;
+pub mod claim_history;
 pub mod config;
+pub mod delegation;
 pub mod events;
+pub mod referral;
+pub mod season;
+pub mod signed_claim;
+pub mod state_override;
+pub mod timelock;
 
-use crate::config::MAX_REPAIR_GAP;
-use multiversx_sc_modules::only_admin;
+use crate::admin::Role;
+use crate::claim_history::ClaimHistoryKind;
+use crate::events::AddressInfoUpdate;
+use crate::signed_claim::SIGNATURE_LENGTH;
 
 #[multiversx_sc::contract]
 pub trait OnChainClaimContract:
-    config::ConfigModule + events::EventsModule + only_admin::OnlyAdminModule
+    config::ConfigModule
+    + access::AccessModule
+    + events::EventsModule
+    + admin::AdminModule
+    + season::SeasonModule
+    + delegation::DelegationModule
+    + signed_claim::SignedClaimModule
+    + state_override::StateOverrideModule
+    + claim_history::ClaimHistoryModule
+    + claim_bitmap::ClaimBitmapModule
+    + badge::BadgeModule
+    + referral::ReferralModule
+    + timelock::TimelockModule
+    + default_issue_callbacks::DefaultIssueCallbacksModule
 {
     #[init]
     fn init(&self, repair_streak_token_id: TokenIdentifier, repair_streak_token_nonce: u64) {
         self.internal_set_repair_streak_payment(repair_streak_token_id, repair_streak_token_nonce);
 
         let caller = self.blockchain().get_caller();
-        self.add_admin(caller);
+        for role in Role::ALL {
+            self.internal_grant_role(role, caller.clone());
+        }
     }
 
     #[upgrade]
-    fn upgrade(&self) {}
+    fn upgrade(&self) {
+        self.migrate_legacy_admins();
+    }
 
     #[endpoint(claim)]
     fn claim(&self) {
+        self.require_not_paused();
         let caller = self.blockchain().get_caller();
-        require!(
-            !self.blockchain().is_smart_contract(&caller),
-            "Only user accounts can perform claim"
-        );
+        self.require_can_claim_for(&caller, "Only user accounts can perform claim");
         self.require_same_shard(&caller);
 
-        let current_epoch = self.blockchain().get_block_epoch();
+        let (current_epoch, update) = self.process_claim(&caller);
+        self.new_claim_event(&caller, current_epoch, &update);
+    }
 
-        let address_info_mapper = self.address_info(&caller);
-        if address_info_mapper.is_empty() {
-            let address_info = AddressInfo::new_with_epoch(current_epoch);
-            self.new_claim_event(&caller, &address_info);
-            self.address_info(&caller).set(address_info);
-            return;
-        }
+    /// First claim of the caller, whose streak milestones earn points to `referrer`.
+    #[endpoint(claimWithReferrer)]
+    fn claim_with_referrer(&self, referrer: ManagedAddress) {
+        self.require_not_paused();
+        let caller = self.blockchain().get_caller();
+        self.require_can_claim_for(&caller, "Only user accounts can perform claim");
+        self.require_same_shard(&caller);
+        self.record_referrer(&caller, &referrer);
 
-        address_info_mapper.update(|address_info| {
-            require!(
-                address_info.last_epoch_claimed < current_epoch,
-                "epoch already claimed"
-            );
+        let (current_epoch, update) = self.process_claim(&caller);
+        self.new_claim_event(&caller, current_epoch, &update);
+    }
 
-            if address_info.last_epoch_claimed + 1 == current_epoch {
-                address_info.current_streak += 1;
-            } else {
-                address_info.current_streak = 1;
-            }
+    /// Claims for `beneficiary`, who authorized the caller through `authorizeDelegate`.
+    #[endpoint(claimFor)]
+    fn claim_for(&self, beneficiary: ManagedAddress) {
+        self.require_not_paused();
+        self.require_caller_is_delegate_of(&beneficiary);
+        self.require_can_claim_for(&beneficiary, "Only user accounts can perform claim");
+        self.require_same_shard(&beneficiary);
+
+        let (current_epoch, update) = self.process_claim(&beneficiary);
+        let delegate = self.blockchain().get_caller();
+        self.new_delegated_claim_event(&beneficiary, current_epoch, &delegate, &update);
+    }
 
-            address_info.total_epochs_claimed += 1;
-            address_info.last_epoch_claimed = current_epoch;
+    /// Claims for `signer`, who signed the claim message for the current epoch and its next nonce.
+    /// Unlike the other claims, the signer can be in any shard.
+    #[endpoint(claimWithSignature)]
+    fn claim_with_signature(
+        &self,
+        signer: ManagedAddress,
+        epoch: u64,
+        nonce: u64,
+        signature: ManagedByteArray<SIGNATURE_LENGTH>,
+    ) {
+        self.require_not_paused();
+        require!(
+            epoch == self.blockchain().get_block_epoch(),
+            "Signature is for another epoch"
+        );
+        self.use_claim_nonce(&signer, nonce);
+        self.require_valid_claim_signature(&signer, epoch, nonce, &signature);
 
-            if address_info.best_streak < address_info.current_streak {
-                address_info.best_streak = address_info.current_streak;
-            }
+        let (current_epoch, update) = self.process_claim(&signer);
+        let relayer = self.blockchain().get_caller();
+        self.new_signed_claim_event(&signer, current_epoch, &relayer, nonce, &update);
+    }
+
+    fn process_claim(&self, address: &ManagedAddress) -> (u64, AddressInfoUpdate) {
+        let current_epoch = self.blockchain().get_block_epoch();
 
-            self.new_claim_event(&caller, address_info);
-        });
+        let previous_address_info = self.get_stored_address_info(address);
+        let (address_info, _) = AddressInfo::claim(previous_address_info.as_ref(), current_epoch)
+            .unwrap_or_else(|err| sc_panic!(err.message()));
+
+        self.record_season_claim(address, current_epoch, 1, address_info.current_streak);
+        self.record_claim_history(address, current_epoch, ClaimHistoryKind::Claim);
+        self.mark_claimed_epoch(address, current_epoch);
+        self.address_info(address).set(&address_info);
+        self.award_badge(address, &address_info);
+        self.reward_referrer(address, &address_info);
+
+        (
+            current_epoch,
+            AddressInfoUpdate::new(previous_address_info.unwrap_or_default(), address_info),
+        )
     }
 
     #[payable("*")]
     #[endpoint(claimAndRepair)]
     fn claim_and_repair(&self) {
+        self.require_not_paused();
         let caller = self.blockchain().get_caller();
-        require!(
-            !self.blockchain().is_smart_contract(&caller),
-            "Only user accounts can perform claim and repair"
-        );
+        self.require_can_claim_for(&caller, "Only user accounts can perform claim and repair");
         self.require_same_shard(&caller);
 
-        let payment = self.call_value().single_esdt();
+        let payment = self.call_value().egld_or_single_esdt();
         let repair_streak_payment = self.repair_streak_payment().get();
         require!(payment == repair_streak_payment, "Bad payment token/amount");
 
         let current_epoch = self.blockchain().get_block_epoch();
 
-        let address_info_mapper = self.address_info(&caller);
-
-        require!(
-            !address_info_mapper.is_empty(),
-            "can't repair streak for address"
+        let previous_address_info = self.get_stored_address_info(&caller);
+        let (address_info, missed_epochs) = AddressInfo::claim_and_repair(
+            previous_address_info.as_ref(),
+            current_epoch,
+            self.get_max_repair_gap_on_claim(),
+        )
+        .unwrap_or_else(|err| sc_panic!(err.message()));
+        self.record_season_claim(
+            &caller,
+            current_epoch,
+            missed_epochs + 1,
+            address_info.current_streak,
         );
-
-        address_info_mapper.update(|address_info| {
-            let missed_epochs =
-                self.get_missed_epochs(current_epoch, address_info.last_epoch_claimed);
-
-            // Allow MAX_REPAIR_GAP + 1 in order to not have failed transaction when the user sends the claimAndRepair transaction
-            // in the last round of the allowed epoch. From UI, we allow MAX_REPAIR_GAP = 5 (using canBeRepaired view)
-            require!(
-                missed_epochs > 0 && missed_epochs <= MAX_REPAIR_GAP + 1,
-                "can't repair streak for current epoch"
-            );
-
-            address_info.current_streak += missed_epochs + 1;
-            address_info.total_epochs_claimed += missed_epochs + 1;
-            address_info.last_epoch_claimed = current_epoch;
-            if address_info.best_streak < address_info.current_streak {
-                address_info.best_streak = address_info.current_streak;
-            }
-
-            self.new_claim_and_repair_event(&caller, address_info);
-        });
-
-        self.send().esdt_local_burn(
-            &payment.token_identifier,
-            payment.token_nonce,
-            &payment.amount,
+        for repaired_epoch in current_epoch - missed_epochs..current_epoch {
+            self.record_claim_history(&caller, repaired_epoch, ClaimHistoryKind::Repair);
+            self.mark_repaired_epoch(&caller, repaired_epoch);
+        }
+        self.record_claim_history(&caller, current_epoch, ClaimHistoryKind::Claim);
+        self.mark_claimed_epoch(&caller, current_epoch);
+        self.address_info(&caller).set(&address_info);
+        self.award_badge(&caller, &address_info);
+        self.reward_referrer(&caller, &address_info);
+
+        self.new_claim_and_repair_event(
+            &caller,
+            current_epoch,
+            missed_epochs,
+            &payment,
+            &AddressInfoUpdate::new(previous_address_info.unwrap_or_default(), address_info),
         );
+
+        self.sink_repair_payment(&payment);
     }
 
+    /// Applied directly, or proposed for the approval of the other state editors in quorum mode.
     #[endpoint(updateState)]
     fn update_state(
         &self,
@@ -128,7 +196,7 @@ pub trait OnChainClaimContract:
         total_epochs_claimed: u64,
         best_streak: u64,
     ) {
-        self.require_caller_is_admin();
+        self.require_caller_has_role(Role::StateEditor);
         self.require_same_shard(address);
 
         let address_info = AddressInfo::new(
@@ -137,42 +205,6 @@ pub trait OnChainClaimContract:
             total_epochs_claimed,
             best_streak,
         );
-        self.address_info(address).set(&address_info);
-
-        self.new_update_state_event(address, &address_info);
-    }
-
-    #[endpoint(setRepairStreakPayment)]
-    fn set_repair_streak_payment(
-        &self,
-        repair_streak_token_identifier: TokenIdentifier,
-        repair_streak_token_nonce: u64,
-    ) {
-        self.require_caller_is_admin();
-
-        self.internal_set_repair_streak_payment(
-            repair_streak_token_identifier,
-            repair_streak_token_nonce,
-        );
-
-        self.new_set_repair_payment_event();
-    }
-
-    fn internal_set_repair_streak_payment(
-        &self,
-        repair_streak_token_identifier: TokenIdentifier,
-        repair_streak_token_nonce: u64,
-    ) {
-        require!(
-            repair_streak_token_identifier.is_valid_esdt_identifier(),
-            "Invalid token ID",
-        );
-
-        let payment = EsdtTokenPayment::new(
-            repair_streak_token_identifier,
-            repair_streak_token_nonce,
-            BigUint::from(1u64),
-        );
-        self.repair_streak_payment().set(payment);
+        self.override_state(address.clone(), address_info);
     }
 }
@@ -0,0 +1,104 @@
+use multiversx_sc::imports::*;
+
+use crate::{
+    admin::{self, Role},
+    events::{self, AdminAction},
+};
+
+/// Delegates allowed by users to claim on their behalf, and smart contracts allowed by the admins to claim.
+#[multiversx_sc::module]
+pub trait DelegationModule: events::EventsModule + admin::AdminModule {
+    #[endpoint(authorizeDelegate)]
+    fn authorize_delegate(&self, delegate: ManagedAddress) {
+        let beneficiary = self.blockchain().get_caller();
+        require!(delegate != beneficiary, "Can't delegate to self");
+        require!(
+            self.delegates(&beneficiary).insert(delegate.clone()),
+            "Delegate already authorized"
+        );
+        self.beneficiaries(&delegate).insert(beneficiary.clone());
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        self.delegate_authorized_event(&beneficiary, &delegate, current_epoch);
+    }
+
+    #[endpoint(revokeDelegate)]
+    fn revoke_delegate(&self, delegate: ManagedAddress) {
+        let beneficiary = self.blockchain().get_caller();
+        require!(
+            self.delegates(&beneficiary).swap_remove(&delegate),
+            "Delegate not authorized"
+        );
+        self.beneficiaries(&delegate).swap_remove(&beneficiary);
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        self.delegate_revoked_event(&beneficiary, &delegate, current_epoch);
+    }
+
+    #[endpoint(addAllowedContract)]
+    fn add_allowed_contract(&self, address: ManagedAddress) {
+        self.require_caller_has_role(Role::RoleManager);
+        require!(
+            self.blockchain().is_smart_contract(&address),
+            "Address is not a smart contract"
+        );
+
+        let was_allowed = !self.allowed_contracts().insert(address.clone());
+
+        self.record_admin_action(AdminAction::AddAllowedContract {
+            address,
+            was_allowed,
+        });
+    }
+
+    #[endpoint(removeAllowedContract)]
+    fn remove_allowed_contract(&self, address: ManagedAddress) {
+        self.require_caller_has_role(Role::RoleManager);
+
+        let was_allowed = self.allowed_contracts().swap_remove(&address);
+
+        self.record_admin_action(AdminAction::RemoveAllowedContract {
+            address,
+            was_allowed,
+        });
+    }
+
+    /// Claims are made for user accounts, or for smart contracts the admins allowed, such as smart wallets.
+    fn require_can_claim_for(&self, address: &ManagedAddress, error_message: &str) {
+        require!(
+            !self.blockchain().is_smart_contract(address)
+                || self.allowed_contracts().contains(address),
+            error_message
+        );
+    }
+
+    fn require_caller_is_delegate_of(&self, beneficiary: &ManagedAddress) {
+        let caller = self.blockchain().get_caller();
+        require!(
+            self.delegates(beneficiary).contains(&caller),
+            "Caller is not an authorized delegate"
+        );
+    }
+
+    #[view(isDelegate)]
+    fn is_delegate(&self, beneficiary: ManagedAddress, delegate: ManagedAddress) -> bool {
+        self.delegates(&beneficiary).contains(&delegate)
+    }
+
+    #[view(isAllowedContract)]
+    fn is_allowed_contract(&self, address: ManagedAddress) -> bool {
+        self.allowed_contracts().contains(&address)
+    }
+
+    #[view(getDelegates)]
+    #[storage_mapper("delegates")]
+    fn delegates(&self, beneficiary: &ManagedAddress) -> UnorderedSetMapper<ManagedAddress>;
+
+    #[view(getBeneficiaries)]
+    #[storage_mapper("beneficiaries")]
+    fn beneficiaries(&self, delegate: &ManagedAddress) -> UnorderedSetMapper<ManagedAddress>;
+
+    #[view(getAllowedContracts)]
+    #[storage_mapper("allowed_contracts")]
+    fn allowed_contracts(&self) -> UnorderedSetMapper<ManagedAddress>;
+}
@@ -1,16 +1,293 @@
-use crate::address_info::*;
+use multiversx_sc::{derive_imports::*, imports::*};
+
+use crate::{
+    address_info::*,
+    admin::Role,
+    badge::{BadgeTier, BadgeTiers},
+    config::RepairPaymentSink,
+    referral::ReferralMilestone,
+    season::Season,
+    state_override::StateOverrideProposal,
+    timelock::ConfigChangeProposal,
+};
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct AddressInfoUpdate {
+    pub previous: AddressInfo,
+    pub current: AddressInfo,
+}
+
+impl AddressInfoUpdate {
+    pub fn new(previous: AddressInfo, current: AddressInfo) -> Self {
+        AddressInfoUpdate { previous, current }
+    }
+}
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode)]
+pub enum AdminAction<M: ManagedTypeApi> {
+    UpdateState {
+        address: ManagedAddress<M>,
+        previous: AddressInfo,
+        current: AddressInfo,
+    },
+    SetRepairStreakPayment {
+        previous: EgldOrEsdtTokenPayment<M>,
+        current: EgldOrEsdtTokenPayment<M>,
+    },
+    /// Only emitted before roles replaced the admin list.
+    AddAdmin {
+        address: ManagedAddress<M>,
+        was_admin: bool,
+    },
+    /// Only emitted before roles replaced the admin list.
+    RemoveAdmin {
+        address: ManagedAddress<M>,
+        was_admin: bool,
+    },
+    OpenSeason {
+        season: Season<M>,
+    },
+    CloseSeason {
+        season_id: u64,
+        previous_end_epoch: u64,
+        end_epoch: u64,
+    },
+    AddAllowedContract {
+        address: ManagedAddress<M>,
+        was_allowed: bool,
+    },
+    RemoveAllowedContract {
+        address: ManagedAddress<M>,
+        was_allowed: bool,
+    },
+    GrantRole {
+        role: Role,
+        address: ManagedAddress<M>,
+        had_role: bool,
+    },
+    RevokeRole {
+        role: Role,
+        address: ManagedAddress<M>,
+        had_role: bool,
+    },
+    SetPaused {
+        paused: bool,
+        was_paused: bool,
+    },
+    SetMaxRepairGap {
+        previous: u64,
+        current: u64,
+    },
+    SetTimelockEpochs {
+        previous: u64,
+        current: u64,
+    },
+    SetStateOverrideQuorum {
+        previous_quorum: u32,
+        quorum: u32,
+        previous_expiry_epochs: u64,
+        expiry_epochs: u64,
+    },
+    SetClaimHistorySize {
+        previous: u32,
+        current: u32,
+    },
+    SetBadgeTiers {
+        previous: BadgeTiers,
+        current: BadgeTiers,
+    },
+    SetReferralMilestones {
+        previous: ManagedVec<M, ReferralMilestone>,
+        current: ManagedVec<M, ReferralMilestone>,
+    },
+    SetRepairPaymentSink {
+        previous: RepairPaymentSink<M>,
+        current: RepairPaymentSink<M>,
+    },
+    ApproveStateOverride {
+        id: u64,
+    },
+    /// Holds the removed proposal.
+    CancelStateOverride {
+        proposal: StateOverrideProposal<M>,
+    },
+    /// Holds the removed proposal.
+    CancelConfigChange {
+        proposal: ConfigChangeProposal<M>,
+    },
+}
 
 #[multiversx_sc::module]
 pub trait EventsModule {
     #[event("new_claim")]
-    fn new_claim_event(&self, #[indexed] address: &ManagedAddress, info: &AddressInfo);
+    fn new_claim_event(
+        &self,
+        #[indexed] address: &ManagedAddress,
+        #[indexed] epoch: u64,
+        update: &AddressInfoUpdate,
+    );
+
+    /// Same layout as `new_claim`, with the delegate that sent the claim as last topic.
+    #[event("new_delegated_claim")]
+    fn new_delegated_claim_event(
+        &self,
+        #[indexed] beneficiary: &ManagedAddress,
+        #[indexed] epoch: u64,
+        #[indexed] delegate: &ManagedAddress,
+        update: &AddressInfoUpdate,
+    );
+
+    /// Same layout as `new_delegated_claim`, with the relayer instead of the delegate and the signature nonce.
+    #[event("new_signed_claim")]
+    fn new_signed_claim_event(
+        &self,
+        #[indexed] signer: &ManagedAddress,
+        #[indexed] epoch: u64,
+        #[indexed] relayer: &ManagedAddress,
+        #[indexed] nonce: u64,
+        update: &AddressInfoUpdate,
+    );
 
     #[event("new_claim_and_repair")]
-    fn new_claim_and_repair_event(&self, #[indexed] address: &ManagedAddress, info: &AddressInfo);
+    fn new_claim_and_repair_event(
+        &self,
+        #[indexed] address: &ManagedAddress,
+        #[indexed] epoch: u64,
+        #[indexed] missed_epochs: u64,
+        #[indexed] payment: &EgldOrEsdtTokenPayment,
+        update: &AddressInfoUpdate,
+    );
 
     #[event("new_update_state")]
-    fn new_update_state_event(&self, #[indexed] address: &ManagedAddress, info: &AddressInfo);
+    fn new_update_state_event(
+        &self,
+        #[indexed] address: &ManagedAddress,
+        #[indexed] epoch: u64,
+        update: &AddressInfoUpdate,
+    );
 
     #[event("new_set_repair_payment")]
-    fn new_set_repair_payment_event(&self);
+    fn new_set_repair_payment_event(
+        &self,
+        #[indexed] epoch: u64,
+        #[indexed] token_identifier: &EgldOrEsdtTokenIdentifier,
+        #[indexed] token_nonce: u64,
+        #[indexed] amount: &BigUint,
+    );
+
+    #[event("delegate_authorized")]
+    fn delegate_authorized_event(
+        &self,
+        #[indexed] beneficiary: &ManagedAddress,
+        #[indexed] delegate: &ManagedAddress,
+        #[indexed] epoch: u64,
+    );
+
+    #[event("delegate_revoked")]
+    fn delegate_revoked_event(
+        &self,
+        #[indexed] beneficiary: &ManagedAddress,
+        #[indexed] delegate: &ManagedAddress,
+        #[indexed] epoch: u64,
+    );
+
+    #[event("config_change_proposed")]
+    fn config_change_proposed_event(
+        &self,
+        #[indexed] id: u64,
+        #[indexed] proposer: &ManagedAddress,
+        #[indexed] epoch: u64,
+        proposal: &ConfigChangeProposal<Self::Api>,
+    );
+
+    #[event("config_change_executed")]
+    fn config_change_executed_event(
+        &self,
+        #[indexed] id: u64,
+        #[indexed] executor: &ManagedAddress,
+        #[indexed] epoch: u64,
+    );
+
+    #[event("config_change_cancelled")]
+    fn config_change_cancelled_event(
+        &self,
+        #[indexed] id: u64,
+        #[indexed] canceller: &ManagedAddress,
+        #[indexed] epoch: u64,
+    );
+
+    #[event("state_override_proposed")]
+    fn state_override_proposed_event(
+        &self,
+        #[indexed] id: u64,
+        #[indexed] proposer: &ManagedAddress,
+        #[indexed] epoch: u64,
+        proposal: &StateOverrideProposal<Self::Api>,
+    );
+
+    #[event("state_override_approved")]
+    fn state_override_approved_event(
+        &self,
+        #[indexed] id: u64,
+        #[indexed] approver: &ManagedAddress,
+        #[indexed] epoch: u64,
+    );
+
+    #[event("state_override_executed")]
+    fn state_override_executed_event(&self, #[indexed] id: u64, #[indexed] epoch: u64);
+
+    #[event("state_override_cancelled")]
+    fn state_override_cancelled_event(
+        &self,
+        #[indexed] id: u64,
+        #[indexed] canceller: &ManagedAddress,
+        #[indexed] epoch: u64,
+    );
+
+    #[event("badge_minted")]
+    fn badge_minted_event(
+        &self,
+        #[indexed] address: &ManagedAddress,
+        #[indexed] nonce: u64,
+        #[indexed] tier: BadgeTier,
+        #[indexed] epoch: u64,
+    );
+
+    #[event("badge_upgraded")]
+    fn badge_upgraded_event(
+        &self,
+        #[indexed] address: &ManagedAddress,
+        #[indexed] nonce: u64,
+        #[indexed] tier: BadgeTier,
+        #[indexed] epoch: u64,
+    );
+
+    #[event("referral_recorded")]
+    fn referral_recorded_event(
+        &self,
+        #[indexed] referee: &ManagedAddress,
+        #[indexed] referrer: &ManagedAddress,
+        #[indexed] epoch: u64,
+    );
+
+    #[event("referral_milestone_reached")]
+    fn referral_milestone_reached_event(
+        &self,
+        #[indexed] referrer: &ManagedAddress,
+        #[indexed] referee: &ManagedAddress,
+        #[indexed] streak: u64,
+        #[indexed] points: u64,
+        #[indexed] epoch: u64,
+    );
+
+    #[event("admin_action")]
+    fn admin_action_event(
+        &self,
+        #[indexed] action_id: u64,
+        #[indexed] admin: &ManagedAddress,
+        #[indexed] epoch: u64,
+        action: &AdminAction<Self::Api>,
+    );
 }
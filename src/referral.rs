@@ -0,0 +1,163 @@
+use multiversx_sc::{derive_imports::*, imports::*};
+
+use crate::{
+    address_info::AddressInfo,
+    admin::{self, Role},
+    config,
+    events::{self, AdminAction},
+};
+
+pub const MAX_REFERRAL_MILESTONES: usize = 10;
+pub const MAX_REFEREES_PAGE_SIZE: usize = 100;
+
+/// Points earned by the referrer once a referee's `best_streak` reaches `streak`.
+#[type_abi]
+#[derive(
+    ManagedVecItem, NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug,
+)]
+pub struct ReferralMilestone {
+    pub streak: u64,
+    pub points: u64,
+}
+
+/// Referrers recorded on the first claim of new users, earning points as their referees reach the milestones.
+#[multiversx_sc::module]
+pub trait ReferralModule: config::ConfigModule + events::EventsModule + admin::AdminModule {
+    /// Replaces the milestones, which must have increasing streaks. Already rewarded streaks are not rewarded again.
+    #[endpoint(setReferralMilestones)]
+    fn set_referral_milestones(&self, milestones: MultiValueEncoded<MultiValue2<u64, u64>>) {
+        self.require_caller_has_role(Role::StateEditor);
+        require!(
+            milestones.len() <= MAX_REFERRAL_MILESTONES,
+            "Too many referral milestones"
+        );
+
+        let mut current = ManagedVec::new();
+        let mut last_streak = 0;
+        for milestone in milestones {
+            let (streak, points) = milestone.into_tuple();
+            require!(
+                streak > last_streak && points > 0,
+                "Invalid referral milestones"
+            );
+            last_streak = streak;
+            current.push(ReferralMilestone { streak, points });
+        }
+
+        let previous = self.referral_milestones().iter().collect();
+        self.referral_milestones().clear();
+        for milestone in current.iter() {
+            self.referral_milestones().push(&milestone);
+        }
+
+        self.record_admin_action(AdminAction::SetReferralMilestones { previous, current });
+    }
+
+    /// Records the referrer of `referee`, which must be claiming for the first time.
+    fn record_referrer(&self, referee: &ManagedAddress, referrer: &ManagedAddress) {
+        require!(
+            self.address_info(referee).is_empty(),
+            "Only the first claim can have a referrer"
+        );
+        require!(referee != referrer, "Can't refer yourself");
+        require!(
+            !self.address_info(referrer).is_empty(),
+            "Referrer never claimed"
+        );
+
+        self.referrer(referee).set(referrer);
+        self.referees(referrer).push(referee);
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        self.referral_recorded_event(referee, referrer, current_epoch);
+    }
+
+    /// Credits the referrer of `referee` with the milestones reached by its `best_streak` and not rewarded yet.
+    fn reward_referrer(&self, referee: &ManagedAddress, address_info: &AddressInfo) {
+        let referrer_mapper = self.referrer(referee);
+        if referrer_mapper.is_empty() {
+            return;
+        }
+
+        let rewarded_streak_mapper = self.referral_rewarded_streak(referee);
+        let rewarded_streak = rewarded_streak_mapper.get();
+        if address_info.best_streak <= rewarded_streak {
+            return;
+        }
+
+        let referrer = referrer_mapper.get();
+        let current_epoch = self.blockchain().get_block_epoch();
+        let mut paid_streak = rewarded_streak;
+        for milestone in self.referral_milestones().iter() {
+            if milestone.streak <= rewarded_streak || milestone.streak > address_info.best_streak {
+                continue;
+            }
+
+            self.referral_points(&referrer)
+                .update(|points| *points = points.saturating_add(milestone.points));
+            self.referral_milestone_reached_event(
+                &referrer,
+                referee,
+                milestone.streak,
+                milestone.points,
+                current_epoch,
+            );
+            paid_streak = milestone.streak;
+        }
+        if paid_streak > rewarded_streak {
+            rewarded_streak_mapper.set(paid_streak);
+        }
+    }
+
+    /// Up to `size` referees of `referrer`, in the order they were referred, starting from index `from`.
+    #[view(getReferees)]
+    fn get_referees(
+        &self,
+        referrer: &ManagedAddress,
+        from: usize,
+        size: usize,
+    ) -> MultiValueEncoded<ManagedAddress> {
+        require!(size <= MAX_REFEREES_PAGE_SIZE, "Page size too large");
+
+        let referees = self.referees(referrer);
+        let end = referees.len().min(from.saturating_add(size));
+        (from..end).map(|index| referees.get(index + 1)).collect()
+    }
+
+    #[view(getRefereeCount)]
+    fn get_referee_count(&self, referrer: &ManagedAddress) -> usize {
+        self.referees(referrer).len()
+    }
+
+    #[view(getReferralMilestones)]
+    fn get_referral_milestones(&self) -> MultiValueEncoded<ReferralMilestone> {
+        self.referral_milestones().iter().collect()
+    }
+
+    #[view(getReferrer)]
+    fn get_referrer(&self, referee: &ManagedAddress) -> OptionalValue<ManagedAddress> {
+        let referrer_mapper = self.referrer(referee);
+        if referrer_mapper.is_empty() {
+            return OptionalValue::None;
+        }
+
+        OptionalValue::Some(referrer_mapper.get())
+    }
+
+    #[storage_mapper("referrer")]
+    fn referrer(&self, referee: &ManagedAddress) -> SingleValueMapper<ManagedAddress>;
+
+    #[storage_mapper("referees")]
+    fn referees(&self, referrer: &ManagedAddress) -> VecMapper<ManagedAddress>;
+
+    #[view(getReferralPoints)]
+    #[storage_mapper("referral_points")]
+    fn referral_points(&self, referrer: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("referral_milestones")]
+    fn referral_milestones(&self) -> VecMapper<ReferralMilestone>;
+
+    /// Streak of the highest milestone of the referee for which its referrer was rewarded.
+    #[storage_mapper("referral_rewarded_streak")]
+    fn referral_rewarded_streak(&self, referee: &ManagedAddress) -> SingleValueMapper<u64>;
+}
@@ -0,0 +1,226 @@
+use multiversx_sc::{derive_imports::*, imports::*};
+
+use crate::{
+    admin::{self, Role},
+    events::{self, AdminAction},
+};
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct Season<M: ManagedTypeApi> {
+    pub id: u64,
+    pub name: ManagedBuffer<M>,
+    pub start_epoch: u64,
+    pub end_epoch: u64,
+}
+
+impl<M: ManagedTypeApi> Season<M> {
+    pub fn contains(&self, epoch: u64) -> bool {
+        self.start_epoch <= epoch && epoch <= self.end_epoch
+    }
+}
+
+/// Streak of an address within a single season, next to its lifetime `AddressInfo`.
+#[type_abi]
+#[derive(Default, NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct SeasonStats {
+    pub season_id: u64,
+    pub current_streak: u64,
+    pub last_epoch_claimed: u64,
+    pub epochs_claimed: u64,
+    pub best_streak: u64,
+}
+
+impl SeasonStats {
+    pub fn new(season_id: u64) -> Self {
+        SeasonStats {
+            season_id,
+            ..Default::default()
+        }
+    }
+
+    /// Records a claim of `claimed_epochs` epochs ending with `current_epoch`, which is inside the season.
+    ///
+    /// The season streak is the part of the lifetime streak since the season started,
+    /// so streaks and repairs carried over from before the season don't count.
+    pub fn record_claim(
+        &mut self,
+        season_start_epoch: u64,
+        current_epoch: u64,
+        claimed_epochs: u64,
+        lifetime_streak: u64,
+    ) {
+        // season_start_epoch <= current_epoch, so the increment can't overflow
+        let season_epochs = current_epoch - season_start_epoch + 1;
+
+        self.current_streak = lifetime_streak.min(season_epochs);
+        self.last_epoch_claimed = current_epoch;
+        self.epochs_claimed = self
+            .epochs_claimed
+            .saturating_add(claimed_epochs.min(season_epochs));
+        if self.best_streak < self.current_streak {
+            self.best_streak = self.current_streak;
+        }
+    }
+}
+
+/// Seasons scheduled by the admins, in which streaks are tracked separately from the lifetime ones.
+#[multiversx_sc::module]
+pub trait SeasonModule: events::EventsModule + admin::AdminModule {
+    #[endpoint(openSeason)]
+    fn open_season(&self, name: ManagedBuffer, start_epoch: u64, end_epoch: u64) -> u64 {
+        self.require_caller_has_role(Role::StateEditor);
+        require!(!name.is_empty(), "Empty season name");
+        require!(start_epoch <= end_epoch, "Season ends before it starts");
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        require!(start_epoch >= current_epoch, "Season starts in the past");
+        if let Some(last_season) = self.last_season() {
+            require!(
+                start_epoch > last_season.end_epoch,
+                "Season overlaps the previous one"
+            );
+        }
+
+        let id = self.seasons().len() as u64 + 1;
+        let season = Season {
+            id,
+            name,
+            start_epoch,
+            end_epoch,
+        };
+        self.seasons().push(&season);
+
+        self.record_admin_action(AdminAction::OpenSeason { season });
+
+        id
+    }
+
+    /// Ends a season with the current epoch, the last one in which claims still count for it.
+    #[endpoint(closeSeason)]
+    fn close_season(&self, season_id: u64) {
+        self.require_caller_has_role(Role::StateEditor);
+        require!(
+            season_id > 0 && season_id as usize <= self.seasons().len(),
+            "Unknown season"
+        );
+
+        let mut season = self.seasons().get(season_id as usize);
+        let current_epoch = self.blockchain().get_block_epoch();
+        require!(season.contains(current_epoch), "Season is not ongoing");
+
+        let previous_end_epoch = season.end_epoch;
+        season.end_epoch = current_epoch;
+        self.seasons().set(season_id as usize, &season);
+
+        self.record_admin_action(AdminAction::CloseSeason {
+            season_id,
+            previous_end_epoch,
+            end_epoch: current_epoch,
+        });
+    }
+
+    fn last_season(&self) -> Option<Season<Self::Api>> {
+        let seasons = self.seasons();
+        if seasons.is_empty() {
+            return None;
+        }
+
+        Some(seasons.get(seasons.len()))
+    }
+
+    fn season_at(&self, epoch: u64) -> Option<Season<Self::Api>> {
+        let seasons = self.seasons();
+        // Seasons are ordered and don't overlap, so the latest one that started is the only candidate
+        for id in (1..=seasons.len()).rev() {
+            let season = seasons.get(id);
+            if season.start_epoch <= epoch {
+                return if season.contains(epoch) {
+                    Some(season)
+                } else {
+                    None
+                };
+            }
+        }
+
+        None
+    }
+
+    /// Records a claim in the stats of the ongoing season, if any.
+    /// Stats of a previous season are archived the first time the address claims in a new one.
+    fn record_season_claim(
+        &self,
+        address: &ManagedAddress,
+        current_epoch: u64,
+        claimed_epochs: u64,
+        lifetime_streak: u64,
+    ) {
+        let Some(season) = self.season_at(current_epoch) else {
+            return;
+        };
+
+        let stats_mapper = self.season_stats(address);
+        let mut stats = SeasonStats::new(season.id);
+        if !stats_mapper.is_empty() {
+            let last_stats = stats_mapper.get();
+            if last_stats.season_id == season.id {
+                stats = last_stats;
+            } else {
+                self.archived_season_stats(address, last_stats.season_id)
+                    .set(last_stats);
+            }
+        }
+
+        stats.record_claim(
+            season.start_epoch,
+            current_epoch,
+            claimed_epochs,
+            lifetime_streak,
+        );
+        stats_mapper.set(stats);
+    }
+
+    #[view(getSeasons)]
+    fn get_seasons(&self) -> MultiValueEncoded<Season<Self::Api>> {
+        self.seasons().iter().collect()
+    }
+
+    #[view(getCurrentSeason)]
+    fn get_current_season(&self) -> OptionalValue<Season<Self::Api>> {
+        let current_epoch = self.blockchain().get_block_epoch();
+
+        self.season_at(current_epoch).into()
+    }
+
+    #[view(getSeasonStats)]
+    fn get_season_stats(&self, address: &ManagedAddress, season_id: u64) -> SeasonStats {
+        let stats_mapper = self.season_stats(address);
+        if !stats_mapper.is_empty() {
+            let stats = stats_mapper.get();
+            if stats.season_id == season_id {
+                return stats;
+            }
+        }
+
+        let archived_stats_mapper = self.archived_season_stats(address, season_id);
+        if archived_stats_mapper.is_empty() {
+            return SeasonStats::new(season_id);
+        }
+
+        archived_stats_mapper.get()
+    }
+
+    #[storage_mapper("seasons")]
+    fn seasons(&self) -> VecMapper<Season<Self::Api>>;
+
+    /// Stats of the last season in which the address claimed.
+    #[storage_mapper("season_stats")]
+    fn season_stats(&self, address: &ManagedAddress) -> SingleValueMapper<SeasonStats>;
+
+    #[storage_mapper("archived_season_stats")]
+    fn archived_season_stats(
+        &self,
+        address: &ManagedAddress,
+        season_id: u64,
+    ) -> SingleValueMapper<SeasonStats>;
+}
@@ -0,0 +1,53 @@
+use multiversx_sc::imports::*;
+
+pub const SIGNATURE_LENGTH: usize = 64;
+/// Length of the signed claim message: signer, epoch, contract address and nonce.
+pub const CLAIM_MESSAGE_LENGTH: usize = 32 + 8 + 32 + 8;
+/// Prefix added by the wallets when signing a message, followed by the message length.
+pub const SIGNED_MESSAGE_PREFIX: &[u8] = b"\x17Elrond Signed Message:\n80";
+
+/// Claims signed by users and submitted by a relayer, for users without gas or in other shards.
+#[multiversx_sc::module]
+pub trait SignedClaimModule {
+    /// The message a user signs to claim `epoch` with `nonce`, as big endian integers.
+    fn claim_message(&self, signer: &ManagedAddress, epoch: u64, nonce: u64) -> ManagedBuffer {
+        let mut message = signer.as_managed_buffer().clone();
+        message.append_bytes(&epoch.to_be_bytes());
+        message.append(self.blockchain().get_sc_address().as_managed_buffer());
+        message.append_bytes(&nonce.to_be_bytes());
+
+        message
+    }
+
+    /// Verifies the signature the same way as the wallets sign messages:
+    /// on the keccak256 hash of the prefixed message, with the signer address as ed25519 public key.
+    fn require_valid_claim_signature(
+        &self,
+        signer: &ManagedAddress,
+        epoch: u64,
+        nonce: u64,
+        signature: &ManagedByteArray<SIGNATURE_LENGTH>,
+    ) {
+        let mut signed_data = ManagedBuffer::new_from_bytes(SIGNED_MESSAGE_PREFIX);
+        signed_data.append(&self.claim_message(signer, epoch, nonce));
+        let hash = self.crypto().keccak256(signed_data);
+
+        self.crypto().verify_ed25519(
+            signer.as_managed_buffer(),
+            hash.as_managed_buffer(),
+            signature.as_managed_buffer(),
+        );
+    }
+
+    /// Consumes the next nonce of the signer, so that a signature can't be replayed.
+    fn use_claim_nonce(&self, signer: &ManagedAddress, nonce: u64) {
+        let next_nonce_mapper = self.next_claim_nonce(signer);
+        require!(nonce == next_nonce_mapper.get(), "Invalid nonce");
+
+        next_nonce_mapper.set(nonce + 1);
+    }
+
+    #[view(getNextClaimNonce)]
+    #[storage_mapper("next_claim_nonce")]
+    fn next_claim_nonce(&self, signer: &ManagedAddress) -> SingleValueMapper<u64>;
+}
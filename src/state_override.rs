@@ -0,0 +1,207 @@
+use multiversx_sc::{derive_imports::*, imports::*};
+
+use crate::{
+    address_info::AddressInfo,
+    admin::{self, Role},
+    badge,
+    claim_history::{self, ClaimHistoryKind},
+    config,
+    events::{self, AddressInfoUpdate, AdminAction},
+};
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct StateOverrideProposal<M: ManagedTypeApi> {
+    pub id: u64,
+    pub address: ManagedAddress<M>,
+    pub address_info: AddressInfo,
+    pub proposer: ManagedAddress<M>,
+    pub proposed_epoch: u64,
+    /// Last epoch in which the proposal can be approved.
+    pub expiry_epoch: u64,
+}
+
+/// State overrides made by the state editors, applied directly or, in quorum mode,
+/// once enough state editors approved them.
+#[multiversx_sc::module]
+pub trait StateOverrideModule:
+    config::ConfigModule
+    + events::EventsModule
+    + admin::AdminModule
+    + claim_history::ClaimHistoryModule
+    + badge::BadgeModule
+{
+    /// A quorum of 0 or 1 applies state overrides directly.
+    #[only_owner]
+    #[endpoint(setStateOverrideQuorum)]
+    fn set_state_override_quorum(&self, quorum: u32, expiry_epochs: u64) {
+        require!(
+            quorum as usize <= self.role_holders(Role::StateEditor).len(),
+            "Quorum exceeds the state editors"
+        );
+        require!(quorum <= 1 || expiry_epochs > 0, "Invalid expiry");
+
+        let previous_quorum = self.state_override_quorum().replace(quorum);
+        let previous_expiry_epochs = self.state_override_expiry_epochs().replace(expiry_epochs);
+
+        self.record_admin_action(AdminAction::SetStateOverrideQuorum {
+            previous_quorum,
+            quorum,
+            previous_expiry_epochs,
+            expiry_epochs,
+        });
+    }
+
+    #[endpoint(approveStateOverride)]
+    fn approve_state_override(&self, id: u64) {
+        self.require_caller_has_role(Role::StateEditor);
+        let proposal = self.get_pending_state_override(id);
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        require!(
+            current_epoch <= proposal.expiry_epoch,
+            "State override expired"
+        );
+
+        let caller = self.blockchain().get_caller();
+        require!(
+            self.state_override_approvals(id).insert(caller.clone()),
+            "State override already approved"
+        );
+        self.state_override_approved_event(id, &caller, current_epoch);
+        self.record_admin_action(AdminAction::ApproveStateOverride { id });
+
+        self.execute_state_override_if_approved(proposal);
+    }
+
+    /// Removes a pending or expired proposal, by its proposer or the owner.
+    #[endpoint(cancelStateOverride)]
+    fn cancel_state_override(&self, id: u64) {
+        let proposal = self.get_pending_state_override(id);
+        let caller = self.blockchain().get_caller();
+        require!(
+            caller == proposal.proposer || caller == self.blockchain().get_owner_address(),
+            "Only the proposer or the owner can cancel"
+        );
+
+        self.remove_state_override(id);
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        self.state_override_cancelled_event(id, &caller, current_epoch);
+        self.record_admin_action(AdminAction::CancelStateOverride { proposal });
+    }
+
+    /// Applies the override directly, or proposes it with the approval of the caller in quorum mode.
+    fn override_state(&self, address: ManagedAddress, address_info: AddressInfo) {
+        if self.state_override_quorum().get() <= 1 {
+            self.apply_state_override(&address, address_info);
+            return;
+        }
+
+        let id = self.last_state_override_id().update(|id| {
+            *id += 1;
+            *id
+        });
+        let current_epoch = self.blockchain().get_block_epoch();
+        let proposer = self.blockchain().get_caller();
+        let proposal = StateOverrideProposal {
+            id,
+            address,
+            address_info,
+            proposer: proposer.clone(),
+            proposed_epoch: current_epoch,
+            expiry_epoch: current_epoch.saturating_add(self.state_override_expiry_epochs().get()),
+        };
+        self.state_override_proposal(id).set(&proposal);
+        self.pending_state_overrides().insert(id);
+        self.state_override_approvals(id).insert(proposer.clone());
+
+        self.state_override_proposed_event(id, &proposer, current_epoch, &proposal);
+    }
+
+    /// Only the approvals of addresses that are still state editors count towards the quorum.
+    fn execute_state_override_if_approved(&self, proposal: StateOverrideProposal<Self::Api>) {
+        let state_editors = self.role_holders(Role::StateEditor);
+        let approvals = self
+            .state_override_approvals(proposal.id)
+            .iter()
+            .filter(|approver| state_editors.contains(approver))
+            .count();
+        if approvals < self.state_override_quorum().get() as usize {
+            return;
+        }
+
+        self.remove_state_override(proposal.id);
+        self.apply_state_override(&proposal.address, proposal.address_info);
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        self.state_override_executed_event(proposal.id, current_epoch);
+    }
+
+    fn apply_state_override(&self, address: &ManagedAddress, address_info: AddressInfo) {
+        let previous_address_info = self.get_address_info(address);
+        self.address_info(address).set(&address_info);
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        self.record_claim_history(address, current_epoch, ClaimHistoryKind::AdminSet);
+        self.award_badge(address, &address_info);
+        self.new_update_state_event(
+            address,
+            current_epoch,
+            &AddressInfoUpdate::new(previous_address_info.clone(), address_info.clone()),
+        );
+        self.record_admin_action(AdminAction::UpdateState {
+            address: address.clone(),
+            previous: previous_address_info,
+            current: address_info,
+        });
+    }
+
+    fn get_pending_state_override(&self, id: u64) -> StateOverrideProposal<Self::Api> {
+        require!(
+            self.pending_state_overrides().contains(&id),
+            "Unknown state override"
+        );
+
+        self.state_override_proposal(id).get()
+    }
+
+    fn remove_state_override(&self, id: u64) {
+        self.pending_state_overrides().swap_remove(&id);
+        self.state_override_proposal(id).clear();
+        self.state_override_approvals(id).clear();
+    }
+
+    /// Pending proposals, including expired ones until they are cancelled.
+    #[view(getPendingStateOverrides)]
+    fn get_pending_state_overrides(&self) -> MultiValueEncoded<StateOverrideProposal<Self::Api>> {
+        self.pending_state_overrides()
+            .iter()
+            .map(|id| self.state_override_proposal(id).get())
+            .collect()
+    }
+
+    #[view(getStateOverrideQuorum)]
+    #[storage_mapper("state_override_quorum")]
+    fn state_override_quorum(&self) -> SingleValueMapper<u32>;
+
+    #[view(getStateOverrideExpiryEpochs)]
+    #[storage_mapper("state_override_expiry_epochs")]
+    fn state_override_expiry_epochs(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("last_state_override_id")]
+    fn last_state_override_id(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("pending_state_overrides")]
+    fn pending_state_overrides(&self) -> UnorderedSetMapper<u64>;
+
+    #[storage_mapper("state_override_proposal")]
+    fn state_override_proposal(
+        &self,
+        id: u64,
+    ) -> SingleValueMapper<StateOverrideProposal<Self::Api>>;
+
+    #[view(getStateOverrideApprovals)]
+    #[storage_mapper("state_override_approvals")]
+    fn state_override_approvals(&self, id: u64) -> UnorderedSetMapper<ManagedAddress>;
+}
@@ -0,0 +1,297 @@
+use multiversx_sc::{derive_imports::*, imports::*};
+
+use crate::{
+    admin::{self, Role},
+    config::{self, RepairPaymentSink},
+    events::{self, AdminAction},
+};
+
+/// Epochs a config change waits before it can be executed, until another delay is set through a config change.
+pub const DEFAULT_TIMELOCK_EPOCHS: u64 = 3;
+
+/// Longest timelock a config change can set, so that a change can't lock every later one forever.
+pub const MAX_TIMELOCK_EPOCHS: u64 = 30;
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub enum ConfigChange<M: ManagedTypeApi> {
+    /// An ESDT payment of one token, see `SetRepairPayment` for EGLD or other amounts.
+    SetRepairStreakPayment {
+        token_identifier: TokenIdentifier<M>,
+        token_nonce: u64,
+    },
+    SetMaxRepairGap {
+        max_repair_gap: u64,
+    },
+    GrantRole {
+        role: Role,
+        address: ManagedAddress<M>,
+    },
+    RevokeRole {
+        role: Role,
+        address: ManagedAddress<M>,
+    },
+    SetTimelockEpochs {
+        timelock_epochs: u64,
+    },
+    SetRepairPaymentSink {
+        sink: RepairPaymentSink<M>,
+    },
+    SetRepairPayment {
+        payment: EgldOrEsdtTokenPayment<M>,
+    },
+}
+
+#[type_abi]
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, Clone, PartialEq, Debug)]
+pub struct ConfigChangeProposal<M: ManagedTypeApi> {
+    pub id: u64,
+    pub change: ConfigChange<M>,
+    pub proposer: ManagedAddress<M>,
+    pub proposed_epoch: u64,
+    pub executable_epoch: u64,
+}
+
+/// Sensitive config changes, which are proposed and can only be executed once the timelock has passed,
+/// leaving time to cancel the ones made with a compromised key.
+#[multiversx_sc::module]
+pub trait TimelockModule: config::ConfigModule + events::EventsModule + admin::AdminModule {
+    #[endpoint(proposeConfigChange)]
+    fn propose_config_change(&self, change: ConfigChange<Self::Api>) -> u64 {
+        self.require_caller_can_propose(&change);
+        self.require_valid_config_change(&change);
+
+        let id = self.last_config_change_id().update(|id| {
+            *id += 1;
+            *id
+        });
+        let current_epoch = self.blockchain().get_block_epoch();
+        let proposal = ConfigChangeProposal {
+            id,
+            change,
+            proposer: self.blockchain().get_caller(),
+            proposed_epoch: current_epoch,
+            executable_epoch: current_epoch.saturating_add(self.get_timelock_epochs()),
+        };
+        self.config_change_proposal(id).set(&proposal);
+        self.pending_config_changes().insert(id);
+
+        self.config_change_proposed_event(id, &proposal.proposer, current_epoch, &proposal);
+
+        id
+    }
+
+    #[endpoint(executeConfigChange)]
+    fn execute_config_change(&self, id: u64) {
+        let proposal = self.get_pending_config_change(id);
+        self.require_caller_can_propose(&proposal.change);
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        require!(
+            current_epoch >= proposal.executable_epoch,
+            "Config change is still timelocked"
+        );
+
+        self.remove_config_change(id);
+        self.apply_config_change(proposal.change);
+
+        let caller = self.blockchain().get_caller();
+        self.config_change_executed_event(id, &caller, current_epoch);
+    }
+
+    /// Proposals can be cancelled by the same roles that can execute them, and by the owner.
+    /// Role changes can only be cancelled by the owner and their proposer,
+    /// so that a compromised role manager can't cancel its own revocation.
+    #[endpoint(cancelConfigChange)]
+    fn cancel_config_change(&self, id: u64) {
+        let proposal = self.get_pending_config_change(id);
+        let caller = self.blockchain().get_caller();
+        if caller != self.blockchain().get_owner_address() {
+            match &proposal.change {
+                ConfigChange::GrantRole { .. } | ConfigChange::RevokeRole { .. } => require!(
+                    caller == proposal.proposer,
+                    "Only the owner or the proposer can cancel a role change"
+                ),
+                _ => self.require_caller_can_propose(&proposal.change),
+            }
+        }
+
+        self.remove_config_change(id);
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        self.config_change_cancelled_event(id, &caller, current_epoch);
+        self.record_admin_action(AdminAction::CancelConfigChange { proposal });
+    }
+
+    fn require_caller_can_propose(&self, change: &ConfigChange<Self::Api>) {
+        match change {
+            ConfigChange::SetRepairStreakPayment { .. }
+            | ConfigChange::SetMaxRepairGap { .. }
+            | ConfigChange::SetRepairPaymentSink { .. }
+            | ConfigChange::SetRepairPayment { .. } => {
+                self.require_caller_has_role(Role::PricingManager)
+            }
+            ConfigChange::GrantRole { .. }
+            | ConfigChange::RevokeRole { .. }
+            | ConfigChange::SetTimelockEpochs { .. } => self.require_caller_can_manage_roles(),
+        }
+    }
+
+    /// Rejects invalid changes when they are proposed, rather than after the timelock.
+    fn require_valid_config_change(&self, change: &ConfigChange<Self::Api>) {
+        match change {
+            ConfigChange::SetRepairStreakPayment {
+                token_identifier, ..
+            } => require!(
+                token_identifier.is_valid_esdt_identifier(),
+                "Invalid token ID"
+            ),
+            ConfigChange::SetMaxRepairGap { max_repair_gap } => {
+                self.require_valid_max_repair_gap(*max_repair_gap)
+            }
+            ConfigChange::SetTimelockEpochs { timelock_epochs } => {
+                self.require_valid_timelock_epochs(*timelock_epochs)
+            }
+            ConfigChange::SetRepairPaymentSink { sink } => {
+                require!(sink.is_valid(), "Invalid repair payment sink");
+                let payment = self.repair_streak_payment().get();
+                self.require_sink_accepts(sink, &payment.token_identifier);
+            }
+            ConfigChange::SetRepairPayment { payment } => {
+                self.require_valid_repair_payment(payment);
+                self.require_sink_accepts(
+                    &self.get_repair_payment_sink(),
+                    &payment.token_identifier,
+                );
+            }
+            ConfigChange::GrantRole { .. } | ConfigChange::RevokeRole { .. } => {}
+        }
+    }
+
+    fn apply_config_change(&self, change: ConfigChange<Self::Api>) {
+        match change {
+            ConfigChange::SetRepairStreakPayment {
+                token_identifier,
+                token_nonce,
+            } => {
+                self.apply_repair_payment(EgldOrEsdtTokenPayment::new(
+                    EgldOrEsdtTokenIdentifier::esdt(token_identifier),
+                    token_nonce,
+                    BigUint::from(1u64),
+                ));
+            }
+            ConfigChange::SetMaxRepairGap { max_repair_gap } => {
+                self.require_valid_max_repair_gap(max_repair_gap);
+                let previous = self.get_max_repair_gap();
+                self.max_repair_gap().set(max_repair_gap);
+
+                self.record_admin_action(AdminAction::SetMaxRepairGap {
+                    previous,
+                    current: max_repair_gap,
+                });
+            }
+            ConfigChange::GrantRole { role, address } => self.internal_grant_role(role, address),
+            ConfigChange::RevokeRole { role, address } => self.internal_revoke_role(role, address),
+            ConfigChange::SetTimelockEpochs { timelock_epochs } => {
+                self.require_valid_timelock_epochs(timelock_epochs);
+                let previous = self.get_timelock_epochs();
+                self.timelock_epochs().set(timelock_epochs);
+
+                self.record_admin_action(AdminAction::SetTimelockEpochs {
+                    previous,
+                    current: timelock_epochs,
+                });
+            }
+            ConfigChange::SetRepairPayment { payment } => self.apply_repair_payment(payment),
+            ConfigChange::SetRepairPaymentSink { sink } => {
+                let payment = self.repair_streak_payment().get();
+                self.require_sink_accepts(&sink, &payment.token_identifier);
+                let previous = self.get_repair_payment_sink();
+                self.repair_payment_sink().set(&sink);
+
+                self.record_admin_action(AdminAction::SetRepairPaymentSink {
+                    previous,
+                    current: sink,
+                });
+            }
+        }
+    }
+
+    fn require_valid_timelock_epochs(&self, timelock_epochs: u64) {
+        require!(
+            timelock_epochs > 0 && timelock_epochs <= MAX_TIMELOCK_EPOCHS,
+            "Invalid timelock"
+        );
+    }
+
+    fn apply_repair_payment(&self, payment: EgldOrEsdtTokenPayment) {
+        let previous_payment = self.repair_streak_payment().get();
+        self.internal_set_repair_payment(payment.clone());
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        self.new_set_repair_payment_event(
+            current_epoch,
+            &payment.token_identifier,
+            payment.token_nonce,
+            &payment.amount,
+        );
+        self.record_admin_action(AdminAction::SetRepairStreakPayment {
+            previous: previous_payment,
+            current: payment,
+        });
+    }
+
+    fn get_pending_config_change(&self, id: u64) -> ConfigChangeProposal<Self::Api> {
+        require!(
+            self.pending_config_changes().contains(&id),
+            "Unknown config change"
+        );
+
+        self.config_change_proposal(id).get()
+    }
+
+    fn remove_config_change(&self, id: u64) {
+        self.pending_config_changes().swap_remove(&id);
+        self.config_change_proposal(id).clear();
+    }
+
+    #[view(getTimelockEpochs)]
+    fn get_timelock_epochs(&self) -> u64 {
+        let timelock_epochs_mapper = self.timelock_epochs();
+        if timelock_epochs_mapper.is_empty() {
+            return DEFAULT_TIMELOCK_EPOCHS;
+        }
+
+        timelock_epochs_mapper.get()
+    }
+
+    #[view(getPendingConfigChanges)]
+    fn get_pending_config_changes(&self) -> MultiValueEncoded<ConfigChangeProposal<Self::Api>> {
+        self.pending_config_changes()
+            .iter()
+            .map(|id| self.config_change_proposal(id).get())
+            .collect()
+    }
+
+    #[view(getConfigChange)]
+    fn get_config_change(&self, id: u64) -> OptionalValue<ConfigChangeProposal<Self::Api>> {
+        if !self.pending_config_changes().contains(&id) {
+            return OptionalValue::None;
+        }
+
+        OptionalValue::Some(self.config_change_proposal(id).get())
+    }
+
+    #[storage_mapper("timelock_epochs")]
+    fn timelock_epochs(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("last_config_change_id")]
+    fn last_config_change_id(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("pending_config_changes")]
+    fn pending_config_changes(&self) -> UnorderedSetMapper<u64>;
+
+    #[storage_mapper("config_change_proposal")]
+    fn config_change_proposal(&self, id: u64)
+        -> SingleValueMapper<ConfigChangeProposal<Self::Api>>;
+}
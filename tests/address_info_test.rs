@@ -0,0 +1,292 @@
+use on_chain_claim::{config::MAX_REPAIR_GAP_ON_CLAIM, AddressInfo, ClaimOutcome, StreakError};
+
+const MAX_EPOCH: u64 = 20;
+const MAX_STREAK: u64 = 4;
+
+/// Every small state reachable through claims and updateState, including inconsistent ones.
+fn all_address_infos() -> impl Iterator<Item = AddressInfo> {
+    (0..=MAX_STREAK).flat_map(|current_streak| {
+        (0..=MAX_EPOCH).flat_map(move |last_epoch_claimed| {
+            (0..=MAX_STREAK).flat_map(move |total_epochs_claimed| {
+                (0..=MAX_STREAK).map(move |best_streak| {
+                    AddressInfo::new(
+                        current_streak,
+                        last_epoch_claimed,
+                        total_epochs_claimed,
+                        best_streak,
+                    )
+                })
+            })
+        })
+    })
+}
+
+#[test]
+fn missed_epochs_test() {
+    let address_info = AddressInfo::new(1, 10, 1, 1);
+
+    assert_eq!(address_info.missed_epochs(5), 0);
+    assert_eq!(address_info.missed_epochs(10), 0);
+    assert_eq!(address_info.missed_epochs(11), 0);
+    assert_eq!(address_info.missed_epochs(12), 1);
+    assert_eq!(address_info.missed_epochs(20), 9);
+}
+
+#[test]
+fn live_streak_test() {
+    let address_info = AddressInfo::new(3, 10, 5, 4);
+
+    assert_eq!(address_info.live_streak(10), 3);
+    assert_eq!(address_info.live_streak(11), 3);
+    assert_eq!(address_info.live_streak(12), 0);
+}
+
+#[test]
+fn claimed_in_last_epochs_test() {
+    let address_info = AddressInfo::new(3, 10, 5, 4);
+
+    assert!(!address_info.claimed_in_last_epochs(10, 0));
+    assert!(address_info.claimed_in_last_epochs(10, 1));
+    assert!(!address_info.claimed_in_last_epochs(11, 1));
+    assert!(address_info.claimed_in_last_epochs(11, 2));
+    assert!(address_info.claimed_in_last_epochs(14, 5));
+    assert!(!address_info.claimed_in_last_epochs(15, 5));
+    // last_epoch_claimed in the future, only possible through updateState
+    assert!(address_info.claimed_in_last_epochs(5, 1));
+}
+
+#[test]
+fn can_be_repaired_test() {
+    let address_info = AddressInfo::new(1, 10, 1, 1);
+
+    assert!(!address_info.can_be_repaired(10, 5));
+    assert!(!address_info.can_be_repaired(11, 5));
+    assert!(address_info.can_be_repaired(12, 5));
+    assert!(address_info.can_be_repaired(16, 5));
+    assert!(!address_info.can_be_repaired(17, 5));
+    assert!(address_info.can_be_repaired(17, 6));
+}
+
+#[test]
+fn first_claim_test() {
+    assert_eq!(
+        AddressInfo::claim(None, 7),
+        Ok((AddressInfo::new(1, 7, 1, 1), ClaimOutcome::Started))
+    );
+}
+
+#[test]
+fn claim_next_epoch_test() {
+    let mut address_info = AddressInfo::new(3, 10, 5, 3);
+
+    assert_eq!(address_info.apply_claim(11), Ok(ClaimOutcome::Extended));
+    assert_eq!(address_info, AddressInfo::new(4, 11, 6, 4));
+}
+
+#[test]
+fn claim_after_missed_epoch_test() {
+    let mut address_info = AddressInfo::new(3, 10, 5, 3);
+
+    assert_eq!(address_info.apply_claim(12), Ok(ClaimOutcome::Reset));
+    assert_eq!(address_info, AddressInfo::new(1, 12, 6, 3));
+}
+
+#[test]
+fn claim_same_epoch_test() {
+    let mut address_info = AddressInfo::new(3, 10, 5, 3);
+
+    assert_eq!(
+        address_info.apply_claim(10),
+        Err(StreakError::EpochAlreadyClaimed)
+    );
+    assert_eq!(
+        address_info.apply_claim(9),
+        Err(StreakError::EpochAlreadyClaimed)
+    );
+    assert_eq!(address_info, AddressInfo::new(3, 10, 5, 3));
+}
+
+#[test]
+fn repair_test() {
+    let mut address_info = AddressInfo::new(3, 10, 5, 3);
+
+    assert_eq!(
+        address_info.apply_repair(14, MAX_REPAIR_GAP_ON_CLAIM),
+        Ok(3)
+    );
+    assert_eq!(address_info, AddressInfo::new(7, 14, 9, 7));
+}
+
+#[test]
+fn repair_without_claim_test() {
+    assert_eq!(
+        AddressInfo::claim_and_repair(None, 10, MAX_REPAIR_GAP_ON_CLAIM),
+        Err(StreakError::NothingToRepair)
+    );
+}
+
+#[test]
+fn repair_not_allowed_test() {
+    let mut address_info = AddressInfo::new(3, 10, 5, 3);
+
+    for current_epoch in [9, 10, 11, 11 + MAX_REPAIR_GAP_ON_CLAIM + 1] {
+        assert_eq!(
+            address_info.apply_repair(current_epoch, MAX_REPAIR_GAP_ON_CLAIM),
+            Err(StreakError::RepairNotAllowed)
+        );
+    }
+    assert_eq!(address_info, AddressInfo::new(3, 10, 5, 3));
+}
+
+#[test]
+fn error_messages_test() {
+    assert_eq!(
+        StreakError::EpochAlreadyClaimed.message(),
+        "epoch already claimed"
+    );
+    assert_eq!(
+        StreakError::NothingToRepair.message(),
+        "can't repair streak for address"
+    );
+    assert_eq!(
+        StreakError::RepairNotAllowed.message(),
+        "can't repair streak for current epoch"
+    );
+}
+
+#[test]
+fn claim_exhaustive_test() {
+    for address_info in all_address_infos() {
+        for current_epoch in 0..=MAX_EPOCH + 2 {
+            let mut claimed = address_info.clone();
+            let result = claimed.apply_claim(current_epoch);
+
+            if current_epoch <= address_info.last_epoch_claimed {
+                assert_eq!(result, Err(StreakError::EpochAlreadyClaimed));
+                assert_eq!(claimed, address_info);
+                continue;
+            }
+
+            let expected_streak = if current_epoch == address_info.last_epoch_claimed + 1 {
+                assert_eq!(result, Ok(ClaimOutcome::Extended));
+                address_info.current_streak + 1
+            } else {
+                assert_eq!(result, Ok(ClaimOutcome::Reset));
+                1
+            };
+            assert_eq!(
+                claimed,
+                AddressInfo::new(
+                    expected_streak,
+                    current_epoch,
+                    address_info.total_epochs_claimed + 1,
+                    address_info.best_streak.max(expected_streak),
+                )
+            );
+            assert_eq!(
+                AddressInfo::claim(Some(&address_info), current_epoch),
+                Ok((claimed, result.unwrap()))
+            );
+        }
+    }
+}
+
+#[test]
+fn repair_exhaustive_test() {
+    for address_info in all_address_infos() {
+        for current_epoch in 0..=MAX_EPOCH + MAX_REPAIR_GAP_ON_CLAIM + 2 {
+            let mut repaired = address_info.clone();
+            let result = repaired.apply_repair(current_epoch, MAX_REPAIR_GAP_ON_CLAIM);
+
+            let missed_epochs = current_epoch.saturating_sub(address_info.last_epoch_claimed + 1);
+            if missed_epochs == 0 || missed_epochs > MAX_REPAIR_GAP_ON_CLAIM {
+                assert_eq!(result, Err(StreakError::RepairNotAllowed));
+                assert_eq!(repaired, address_info);
+                continue;
+            }
+
+            let expected_streak = address_info.current_streak + missed_epochs + 1;
+            assert_eq!(result, Ok(missed_epochs));
+            assert_eq!(
+                repaired,
+                AddressInfo::new(
+                    expected_streak,
+                    current_epoch,
+                    address_info.total_epochs_claimed + missed_epochs + 1,
+                    address_info.best_streak.max(expected_streak),
+                )
+            );
+            assert_eq!(
+                AddressInfo::claim_and_repair(
+                    Some(&address_info),
+                    current_epoch,
+                    MAX_REPAIR_GAP_ON_CLAIM
+                ),
+                Ok((repaired, missed_epochs))
+            );
+        }
+    }
+}
+
+#[test]
+fn repair_matches_claiming_every_missed_epoch_test() {
+    for address_info in all_address_infos() {
+        let first_epoch = address_info.last_epoch_claimed + 2;
+        for current_epoch in first_epoch..first_epoch + MAX_REPAIR_GAP_ON_CLAIM {
+            let mut repaired = address_info.clone();
+            repaired
+                .apply_repair(current_epoch, MAX_REPAIR_GAP_ON_CLAIM)
+                .unwrap();
+
+            let mut claimed = address_info.clone();
+            for epoch in address_info.last_epoch_claimed + 1..=current_epoch {
+                claimed.apply_claim(epoch).unwrap();
+            }
+
+            assert_eq!(repaired, claimed);
+        }
+    }
+}
+
+#[test]
+fn claim_saturates_test() {
+    let mut address_info = AddressInfo::new(u64::MAX, 10, u64::MAX, u64::MAX);
+
+    assert_eq!(address_info.apply_claim(11), Ok(ClaimOutcome::Extended));
+    assert_eq!(
+        address_info,
+        AddressInfo::new(u64::MAX, 11, u64::MAX, u64::MAX)
+    );
+}
+
+#[test]
+fn repair_saturates_test() {
+    let mut address_info = AddressInfo::new(u64::MAX - 1, 10, u64::MAX - 2, 0);
+
+    assert_eq!(
+        address_info.apply_repair(13, MAX_REPAIR_GAP_ON_CLAIM),
+        Ok(2)
+    );
+    assert_eq!(
+        address_info,
+        AddressInfo::new(u64::MAX, 13, u64::MAX, u64::MAX)
+    );
+}
+
+#[test]
+fn claim_at_last_epoch_test() {
+    let mut address_info = AddressInfo::new(1, u64::MAX - 1, 1, 1);
+
+    assert_eq!(
+        address_info.apply_claim(u64::MAX),
+        Ok(ClaimOutcome::Extended)
+    );
+    assert_eq!(
+        address_info.apply_claim(u64::MAX),
+        Err(StreakError::EpochAlreadyClaimed)
+    );
+    assert_eq!(
+        address_info.apply_repair(u64::MAX, MAX_REPAIR_GAP_ON_CLAIM),
+        Err(StreakError::RepairNotAllowed)
+    );
+}
@@ -0,0 +1,49 @@
+use on_chain_claim::badge::{BadgeTier, BadgeTiers};
+
+const TIERS: BadgeTiers = BadgeTiers {
+    bronze: 7,
+    silver: 30,
+    gold: 100,
+};
+
+#[test]
+fn tier_for_best_streak_test() {
+    assert_eq!(TIERS.tier_for(0), None);
+    assert_eq!(TIERS.tier_for(6), None);
+    assert_eq!(TIERS.tier_for(7), Some(BadgeTier::Bronze));
+    assert_eq!(TIERS.tier_for(29), Some(BadgeTier::Bronze));
+    assert_eq!(TIERS.tier_for(30), Some(BadgeTier::Silver));
+    assert_eq!(TIERS.tier_for(100), Some(BadgeTier::Gold));
+    assert_eq!(TIERS.tier_for(u64::MAX), Some(BadgeTier::Gold));
+}
+
+#[test]
+fn unset_tiers_award_nothing_test() {
+    let tiers = BadgeTiers::default();
+
+    assert!(!tiers.is_valid());
+    assert_eq!(tiers.tier_for(u64::MAX), None);
+}
+
+#[test]
+fn tiers_must_increase_test() {
+    assert!(TIERS.is_valid());
+    assert!(!BadgeTiers {
+        bronze: 7,
+        silver: 7,
+        gold: 100,
+    }
+    .is_valid());
+    assert!(!BadgeTiers {
+        bronze: 7,
+        silver: 100,
+        gold: 30,
+    }
+    .is_valid());
+}
+
+#[test]
+fn tiers_are_ordered_test() {
+    assert!(BadgeTier::Bronze < BadgeTier::Silver);
+    assert!(BadgeTier::Silver < BadgeTier::Gold);
+}
@@ -0,0 +1,674 @@
+//! End-to-end tests against a local chain simulator.
+//!
+//! Only built with `--features chain-simulator-tests`, since they need a running simulator:
+//! `CHAIN_SIMULATOR_URL` (default `http://localhost:8085`) must point to its HTTP API.
+//! Blocks are generated by the tests themselves, there is no need to run anything alongside the simulator.
+#![cfg(feature = "chain-simulator-tests")]
+
+use multiversx_sc_snippets::{imports::*, sdk::wallet::Wallet, test_wallets};
+use serde_json::{json, Value};
+use std::{future::Future, time::Duration};
+
+mod proxy;
+
+use on_chain_claim::timelock::DEFAULT_TIMELOCK_EPOCHS;
+use proxy::{AddressInfo, ConfigChange, Role};
+
+const DEFAULT_CHAIN_SIMULATOR_URL: &str = "http://localhost:8085";
+const CODE_EXPR: &str = "mxsc:output/on-chain-claim.mxsc.json";
+const INITIAL_BALANCE: &str = "1000000000000000000000"; // 1000 EGLD
+const ISSUE_COST: u64 = 50_000_000_000_000_000; // 0.05 EGLD
+const SFT_SUPPLY: u64 = 100;
+const BLOCK_INTERVAL: Duration = Duration::from_millis(200);
+// Blocks generated after a cross-shard transaction, so that all its results get executed
+const CROSS_SHARD_SETTLE_BLOCKS: u64 = 10;
+
+/// Thin client over the chain simulator's HTTP API.
+#[derive(Clone)]
+struct ChainSimulator {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl ChainSimulator {
+    fn from_env() -> Self {
+        ChainSimulator {
+            url: std::env::var("CHAIN_SIMULATOR_URL")
+                .unwrap_or_else(|_| DEFAULT_CHAIN_SIMULATOR_URL.to_string()),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    async fn post(&self, path: &str, body: Option<Value>) -> Value {
+        let mut request = self.client.post(format!("{}{path}", self.url));
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+
+        let response: Value = request
+            .send()
+            .await
+            .expect("chain simulator unreachable")
+            .json()
+            .await
+            .expect("invalid chain simulator response");
+        assert_eq!(
+            response["code"], "successful",
+            "chain simulator request {path} failed: {response}"
+        );
+
+        response
+    }
+
+    async fn generate_blocks(&self, num_blocks: u64) {
+        self.post(&format!("/simulator/generate-blocks/{num_blocks}"), None)
+            .await;
+    }
+
+    async fn generate_blocks_until_epoch(&self, epoch: u64) {
+        self.post(
+            &format!("/simulator/generate-blocks-until-epoch-reached/{epoch}"),
+            None,
+        )
+        .await;
+    }
+
+    async fn current_epoch(&self, shard: u32) -> u64 {
+        let response: Value = self
+            .client
+            .get(format!("{}/network/status/{shard}", self.url))
+            .send()
+            .await
+            .expect("chain simulator unreachable")
+            .json()
+            .await
+            .expect("invalid network status response");
+
+        response["data"]["status"]["erd_epoch_number"]
+            .as_u64()
+            .expect("missing epoch in network status")
+    }
+
+    async fn fund(&self, addresses: &[Address]) {
+        let accounts = addresses
+            .iter()
+            .map(|address| {
+                json!({
+                    "address": Bech32Address::from(address).to_bech32_string(),
+                    "balance": INITIAL_BALANCE,
+                })
+            })
+            .collect::<Vec<_>>();
+        self.post("/simulator/set-state", Some(Value::Array(accounts)))
+            .await;
+    }
+
+    /// Produces blocks until the given transaction future completes.
+    ///
+    /// Blocks are only produced while waiting for transactions, so that epochs change solely in `generate_blocks_until_epoch`.
+    async fn produce_blocks_while<F: Future>(&self, tx: F) -> F::Output {
+        let produce_blocks = async {
+            loop {
+                self.generate_blocks(1).await;
+                tokio::time::sleep(BLOCK_INTERVAL).await;
+            }
+        };
+
+        tokio::select! {
+            output = tx => output,
+            _ = produce_blocks => unreachable!(),
+        }
+    }
+}
+
+/// Same computation as the protocol's shard coordinator.
+fn shard_of(address: &Address, num_shards: u32) -> u32 {
+    let last_byte = address.as_array()[31] as u32;
+    let num_bits = u32::BITS - (num_shards - 1).leading_zeros();
+    let mask_high = (1 << num_bits) - 1;
+    let mask_low = (1 << (num_bits - 1)) - 1;
+
+    let shard = last_byte & mask_high;
+    if shard < num_shards {
+        shard
+    } else {
+        last_byte & mask_low
+    }
+}
+
+fn esdt_system_sc_address() -> Bech32Address {
+    Bech32Address::from_bech32_string(ESDTSystemSCAddress.to_bech32_str().to_string())
+}
+
+fn address_info(
+    current_streak: u64,
+    last_epoch_claimed: u64,
+    total_epochs_claimed: u64,
+    best_streak: u64,
+) -> AddressInfo {
+    AddressInfo {
+        current_streak,
+        last_epoch_claimed,
+        total_epochs_claimed,
+        best_streak,
+    }
+}
+
+struct TestEnv {
+    simulator: ChainSimulator,
+    interactor: Interactor,
+    owner: Address,
+    users: Vec<Address>,
+    other_shard_user: Address,
+    contract: Bech32Address,
+    contract_shard: u32,
+    token_id: String,
+    token_nonce: u64,
+}
+
+impl TestEnv {
+    async fn new() -> Self {
+        let simulator = ChainSimulator::from_env();
+        let mut interactor = Interactor::new(&simulator.url).await;
+        let num_shards = interactor.network_config.num_shards_without_meta;
+
+        let wallets: Vec<Wallet> = vec![
+            test_wallets::alice(),
+            test_wallets::bob(),
+            test_wallets::carol(),
+            test_wallets::dan(),
+            test_wallets::eve(),
+            test_wallets::frank(),
+            test_wallets::grace(),
+            test_wallets::heidi(),
+            test_wallets::ivan(),
+            test_wallets::judy(),
+            test_wallets::mallory(),
+            test_wallets::mike(),
+        ];
+        let addresses: Vec<Address> = wallets
+            .into_iter()
+            .map(|wallet| interactor.register_wallet(wallet))
+            .collect();
+        simulator.fund(&addresses).await;
+
+        // The contract is deployed in the owner's shard
+        let owner = addresses[0].clone();
+        let contract_shard = shard_of(&owner, num_shards);
+        let (users, other_shard_users): (Vec<Address>, Vec<Address>) = addresses[1..]
+            .iter()
+            .cloned()
+            .partition(|address| shard_of(address, num_shards) == contract_shard);
+        assert!(
+            users.len() >= 2,
+            "not enough test wallets in the owner's shard"
+        );
+        let other_shard_user = other_shard_users
+            .first()
+            .cloned()
+            .expect("no test wallet outside of the owner's shard");
+
+        let mut env = TestEnv {
+            simulator,
+            interactor,
+            owner,
+            users,
+            other_shard_user,
+            contract: Bech32Address::from(Address::zero()),
+            contract_shard,
+            token_id: String::new(),
+            token_nonce: 0,
+        };
+        env.issue_repair_token().await;
+        env.deploy().await;
+        env.allow_contract_to_burn().await;
+
+        env
+    }
+
+    /// Issues the SFT used for repairs and gives some of it to every user.
+    async fn issue_repair_token(&mut self) {
+        let esdt_system_sc = esdt_system_sc_address();
+        self.token_id = self
+            .simulator
+            .produce_blocks_while(
+                self.interactor
+                    .tx()
+                    .from(&self.owner)
+                    .to(&esdt_system_sc)
+                    .gas(NumExpr("100,000,000"))
+                    .typed(ESDTSystemSCProxy)
+                    .issue_semi_fungible(
+                        BigUint::from(ISSUE_COST),
+                        &ManagedBuffer::from("RepairStreak"),
+                        &ManagedBuffer::from("REPAIR"),
+                        SemiFungibleTokenProperties {
+                            can_add_special_roles: true,
+                            ..Default::default()
+                        },
+                    )
+                    .returns(ReturnsNewTokenIdentifier)
+                    .prepare_async()
+                    .run(),
+            )
+            .await;
+        self.simulator
+            .generate_blocks(CROSS_SHARD_SETTLE_BLOCKS)
+            .await;
+
+        let owner = self.owner.clone();
+        self.set_special_roles(&owner, EsdtLocalRole::NftCreate)
+            .await;
+
+        self.token_nonce = self
+            .simulator
+            .produce_blocks_while(
+                self.interactor
+                    .tx()
+                    .from(&self.owner)
+                    .to(&self.owner)
+                    .gas(NumExpr("10,000,000"))
+                    .typed(UserBuiltinProxy)
+                    .esdt_nft_create(
+                        &TokenIdentifier::from(self.token_id.as_str()),
+                        &BigUint::from(SFT_SUPPLY),
+                        &ManagedBuffer::from("RepairStreak"),
+                        &BigUint::zero(),
+                        &ManagedBuffer::new(),
+                        &ManagedBuffer::<StaticApi>::new(),
+                        &ManagedVec::new(),
+                    )
+                    .returns(ReturnsResultUnmanaged)
+                    .prepare_async()
+                    .run(),
+            )
+            .await;
+
+        for user in self.users.clone() {
+            self.simulator
+                .produce_blocks_while(
+                    self.interactor
+                        .tx()
+                        .from(&self.owner)
+                        .to(&user)
+                        .gas(NumExpr("1,000,000"))
+                        .single_esdt(
+                            &TokenIdentifier::from(self.token_id.as_str()),
+                            self.token_nonce,
+                            &BigUint::from(10u64),
+                        )
+                        .prepare_async()
+                        .run(),
+                )
+                .await;
+        }
+    }
+
+    async fn set_special_roles(&mut self, address: &Address, role: EsdtLocalRole) {
+        let esdt_system_sc = esdt_system_sc_address();
+        self.simulator
+            .produce_blocks_while(
+                self.interactor
+                    .tx()
+                    .from(&self.owner)
+                    .to(&esdt_system_sc)
+                    .gas(NumExpr("100,000,000"))
+                    .typed(ESDTSystemSCProxy)
+                    .set_special_roles(
+                        &ManagedAddress::from(address),
+                        &TokenIdentifier::from(self.token_id.as_str()),
+                        [role].into_iter(),
+                    )
+                    .prepare_async()
+                    .run(),
+            )
+            .await;
+        self.simulator
+            .generate_blocks(CROSS_SHARD_SETTLE_BLOCKS)
+            .await;
+    }
+
+    async fn deploy(&mut self) {
+        let contract_code = BytesValue::interpret_from(CODE_EXPR, &InterpreterContext::default());
+
+        let new_address = self
+            .simulator
+            .produce_blocks_while(
+                self.interactor
+                    .tx()
+                    .from(&self.owner)
+                    .gas(NumExpr("30,000,000"))
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .init(
+                        TokenIdentifier::from(self.token_id.as_str()),
+                        self.token_nonce,
+                    )
+                    .code(&contract_code)
+                    .returns(ReturnsNewAddress)
+                    .prepare_async()
+                    .run(),
+            )
+            .await;
+        self.contract = Bech32Address::from(new_address);
+    }
+
+    async fn allow_contract_to_burn(&mut self) {
+        let contract = self.contract.to_address();
+        self.set_special_roles(&contract, EsdtLocalRole::NftBurn)
+            .await;
+    }
+
+    /// Moves the chain forward by the given number of epochs.
+    async fn advance_epochs(&self, num_epochs: u64) -> u64 {
+        let target_epoch = self.current_epoch().await + num_epochs;
+        self.simulator
+            .generate_blocks_until_epoch(target_epoch)
+            .await;
+
+        target_epoch
+    }
+
+    async fn current_epoch(&self) -> u64 {
+        self.simulator.current_epoch(self.contract_shard).await
+    }
+
+    async fn claim(&mut self, user: &Address) {
+        self.simulator
+            .produce_blocks_while(
+                self.interactor
+                    .tx()
+                    .from(user)
+                    .to(&self.contract)
+                    .gas(NumExpr("30,000,000"))
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .claim()
+                    .prepare_async()
+                    .run(),
+            )
+            .await;
+    }
+
+    async fn claim_expect_error(&mut self, user: &Address, message: &str) {
+        self.simulator
+            .produce_blocks_while(
+                self.interactor
+                    .tx()
+                    .from(user)
+                    .to(&self.contract)
+                    .gas(NumExpr("30,000,000"))
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .claim()
+                    .returns(ExpectError(4, message))
+                    .prepare_async()
+                    .run(),
+            )
+            .await;
+    }
+
+    async fn claim_and_repair(&mut self, user: &Address, amount: u64) {
+        self.simulator
+            .produce_blocks_while(
+                self.interactor
+                    .tx()
+                    .from(user)
+                    .to(&self.contract)
+                    .gas(NumExpr("30,000,000"))
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .claim_and_repair()
+                    .single_esdt(
+                        &TokenIdentifier::from(self.token_id.as_str()),
+                        self.token_nonce,
+                        &BigUint::from(amount),
+                    )
+                    .prepare_async()
+                    .run(),
+            )
+            .await;
+    }
+
+    async fn claim_and_repair_expect_error(&mut self, user: &Address, amount: u64, message: &str) {
+        self.simulator
+            .produce_blocks_while(
+                self.interactor
+                    .tx()
+                    .from(user)
+                    .to(&self.contract)
+                    .gas(NumExpr("30,000,000"))
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .claim_and_repair()
+                    .single_esdt(
+                        &TokenIdentifier::from(self.token_id.as_str()),
+                        self.token_nonce,
+                        &BigUint::from(amount),
+                    )
+                    .returns(ExpectError(4, message))
+                    .prepare_async()
+                    .run(),
+            )
+            .await;
+    }
+
+    async fn update_state(&mut self, admin: &Address, address: &Address, state: AddressInfo) {
+        self.simulator
+            .produce_blocks_while(
+                self.interactor
+                    .tx()
+                    .from(admin)
+                    .to(&self.contract)
+                    .gas(NumExpr("30,000,000"))
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .update_state(
+                        address,
+                        state.current_streak,
+                        state.last_epoch_claimed,
+                        state.total_epochs_claimed,
+                        state.best_streak,
+                    )
+                    .prepare_async()
+                    .run(),
+            )
+            .await;
+    }
+
+    async fn update_state_expect_error(
+        &mut self,
+        admin: &Address,
+        address: &Address,
+        message: &str,
+    ) {
+        self.simulator
+            .produce_blocks_while(
+                self.interactor
+                    .tx()
+                    .from(admin)
+                    .to(&self.contract)
+                    .gas(NumExpr("30,000,000"))
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .update_state(address, 1u64, 1u64, 1u64, 1u64)
+                    .returns(ExpectError(4, message))
+                    .prepare_async()
+                    .run(),
+            )
+            .await;
+    }
+
+    /// Proposes a config change as the owner, then executes it once the timelock has passed.
+    async fn apply_config_change(&mut self, change: ConfigChange<StaticApi>) {
+        let id = self
+            .simulator
+            .produce_blocks_while(
+                self.interactor
+                    .tx()
+                    .from(&self.owner)
+                    .to(&self.contract)
+                    .gas(NumExpr("30,000,000"))
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .propose_config_change(change)
+                    .returns(ReturnsResultUnmanaged)
+                    .prepare_async()
+                    .run(),
+            )
+            .await;
+
+        self.advance_epochs(DEFAULT_TIMELOCK_EPOCHS).await;
+        self.simulator
+            .produce_blocks_while(
+                self.interactor
+                    .tx()
+                    .from(&self.owner)
+                    .to(&self.contract)
+                    .gas(NumExpr("30,000,000"))
+                    .typed(proxy::OnChainClaimContractProxy)
+                    .execute_config_change(id)
+                    .prepare_async()
+                    .run(),
+            )
+            .await;
+    }
+
+    async fn grant_role(&mut self, role: Role, address: &Address) {
+        self.apply_config_change(ConfigChange::GrantRole {
+            role,
+            address: address.into(),
+        })
+        .await;
+    }
+
+    async fn revoke_role(&mut self, role: Role, address: &Address) {
+        self.apply_config_change(ConfigChange::RevokeRole {
+            role,
+            address: address.into(),
+        })
+        .await;
+    }
+
+    async fn address_info(&mut self, address: &Address) -> AddressInfo {
+        self.interactor
+            .query()
+            .to(&self.contract)
+            .typed(proxy::OnChainClaimContractProxy)
+            .get_address_info(address)
+            .returns(ReturnsResultUnmanaged)
+            .prepare_async()
+            .run()
+            .await
+    }
+
+    async fn has_role(&mut self, role: Role, address: &Address) -> bool {
+        self.interactor
+            .query()
+            .to(&self.contract)
+            .typed(proxy::OnChainClaimContractProxy)
+            .has_role(role, address)
+            .returns(ReturnsResultUnmanaged)
+            .prepare_async()
+            .run()
+            .await
+    }
+
+    async fn admin_action_count(&mut self) -> u64 {
+        self.interactor
+            .query()
+            .to(&self.contract)
+            .typed(proxy::OnChainClaimContractProxy)
+            .admin_action_count()
+            .returns(ReturnsResultUnmanaged)
+            .prepare_async()
+            .run()
+            .await
+    }
+}
+
+// A single test, since all the flows share the simulator's epoch
+#[tokio::test]
+async fn chain_simulator_test() {
+    let mut env = TestEnv::new().await;
+
+    claim_flow(&mut env).await;
+    claim_and_repair_flow(&mut env).await;
+    wrong_shard_flow(&mut env).await;
+    admin_flow(&mut env).await;
+}
+
+async fn claim_flow(env: &mut TestEnv) {
+    let user = env.users[0].clone();
+
+    // Start right after an epoch change, so that the following transactions share the same epoch
+    let epoch = env.advance_epochs(1).await;
+    env.claim(&user).await;
+    assert_eq!(env.address_info(&user).await, address_info(1, epoch, 1, 1));
+
+    env.claim_expect_error(&user, "epoch already claimed").await;
+
+    let epoch = env.advance_epochs(1).await;
+    env.claim(&user).await;
+    assert_eq!(env.address_info(&user).await, address_info(2, epoch, 2, 2));
+
+    // Missing an epoch resets the current streak
+    let epoch = env.advance_epochs(2).await;
+    env.claim(&user).await;
+    assert_eq!(env.address_info(&user).await, address_info(1, epoch, 3, 2));
+}
+
+async fn claim_and_repair_flow(env: &mut TestEnv) {
+    let user = env.users[1].clone();
+
+    env.claim_and_repair_expect_error(&user, 1, "can't repair streak for address")
+        .await;
+
+    env.advance_epochs(1).await;
+    env.claim(&user).await;
+    let epoch = env.advance_epochs(1).await;
+    env.claim(&user).await;
+    assert_eq!(env.address_info(&user).await, address_info(2, epoch, 2, 2));
+
+    env.claim_and_repair_expect_error(&user, 1, "can't repair streak for current epoch")
+        .await;
+
+    // Three missed epochs, repaired along with the current one
+    let epoch = env.advance_epochs(4).await;
+    env.claim_and_repair_expect_error(&user, 2, "Bad payment token/amount")
+        .await;
+    env.claim_and_repair(&user, 1).await;
+    assert_eq!(env.address_info(&user).await, address_info(6, epoch, 6, 6));
+
+    // Too many missed epochs
+    env.advance_epochs(8).await;
+    env.claim_and_repair_expect_error(&user, 1, "can't repair streak for current epoch")
+        .await;
+}
+
+async fn wrong_shard_flow(env: &mut TestEnv) {
+    let user = env.other_shard_user.clone();
+    let owner = env.owner.clone();
+
+    env.claim_expect_error(&user, "wrong shard").await;
+    env.update_state_expect_error(&owner, &user, "wrong shard")
+        .await;
+}
+
+async fn admin_flow(env: &mut TestEnv) {
+    let owner = env.owner.clone();
+    let admin = env.users[0].clone();
+    let user = env.users[1].clone();
+    let admin_action_count = env.admin_action_count().await;
+
+    env.update_state_expect_error(&admin, &user, "Caller doesn't have the required role")
+        .await;
+
+    env.grant_role(Role::StateEditor, &admin).await;
+    assert!(env.has_role(Role::StateEditor, &admin).await);
+
+    let state = address_info(10, env.current_epoch().await, 20, 15);
+    env.update_state(&admin, &user, state.clone()).await;
+    assert_eq!(env.address_info(&user).await, state);
+
+    env.revoke_role(Role::StateEditor, &admin).await;
+    assert!(!env.has_role(Role::StateEditor, &admin).await);
+    env.update_state_expect_error(&admin, &user, "Caller doesn't have the required role")
+        .await;
+
+    // the role changes and updateState were audited
+    assert_eq!(env.admin_action_count().await, admin_action_count + 3);
+    assert!(env.has_role(Role::StateEditor, &owner).await);
+}
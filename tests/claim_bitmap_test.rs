@@ -0,0 +1,48 @@
+use on_chain_claim::claim_bitmap::{ClaimBitmapWord, EPOCHS_PER_WORD};
+
+fn full_word(first_epoch: u64) -> ClaimBitmapWord {
+    ClaimBitmapWord {
+        first_epoch,
+        claimed: u64::MAX,
+        repaired: u64::MAX,
+    }
+}
+
+#[test]
+fn range_covering_word_keeps_all_bits_test() {
+    let word = full_word(64).masked(0, 1000);
+
+    assert_eq!(word, full_word(64));
+}
+
+#[test]
+fn range_start_clears_lower_bits_test() {
+    let word = full_word(64).masked(66, 1000);
+
+    assert_eq!(word.claimed, u64::MAX << 2);
+    assert_eq!(word.repaired, u64::MAX << 2);
+}
+
+#[test]
+fn range_end_clears_upper_bits_test() {
+    let word = full_word(64).masked(0, 64 + 9);
+
+    assert_eq!(word.claimed, (1 << 10) - 1);
+    assert_eq!(word.repaired, (1 << 10) - 1);
+}
+
+#[test]
+fn range_inside_word_test() {
+    let word = full_word(0).masked(3, 3);
+
+    assert_eq!(word.claimed, 1 << 3);
+    assert_eq!(word.repaired, 1 << 3);
+}
+
+#[test]
+fn range_on_word_bounds_test() {
+    let last_epoch = EPOCHS_PER_WORD - 1;
+
+    assert_eq!(full_word(0).masked(0, last_epoch), full_word(0));
+    assert_eq!(full_word(0).masked(last_epoch, last_epoch).claimed, 1 << 63);
+}
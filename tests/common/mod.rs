@@ -0,0 +1,108 @@
+//! Helpers shared by the test suites that generate and run scenarios.
+// Each suite only uses some of them
+#![allow(dead_code)]
+
+use multiversx_sc_scenario::{
+    imports::*,
+    scenario_format::interpret_trait::{InterpretableFrom, InterpreterContext},
+    scenario_model::{
+        Account, BytesKey, BytesValue, CheckLog, CheckLogList, CheckLogs, CheckValue, TxExpect,
+        TxResponse,
+    },
+};
+
+/// The scenarios written by the test suites, sorted by path.
+pub fn scenario_paths() -> Vec<String> {
+    let mut paths: Vec<String> = std::fs::read_dir("scenarios")
+        .expect("scenarios folder not found")
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .filter(|file_name| file_name.ends_with(".scen.json"))
+        .map(|file_name| format!("scenarios/{file_name}"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Puts the contract at `contract`, holding the burn role of `token` and paid with one `token` for repairs.
+///
+/// The burn role can't be granted to the contract after deploy through a step recorded in the trace,
+/// so the contract is set up directly, with the storage written by `init`.
+pub fn setup_contract_with_burn_role(
+    world: &mut ScenarioWorld,
+    code_path: MxscPath,
+    owner: TestAddress,
+    contract: TestSCAddress,
+    token: TestTokenIdentifier,
+) {
+    let context = InterpreterContext::new()
+        .with_dir(world.current_dir().clone())
+        .with_allowed_missing_files();
+    let mut account = Account::new()
+        .code(BytesValue::interpret_from(
+            code_path.eval_to_expr(),
+            &context,
+        ))
+        .owner(owner.eval_to_expr().as_str())
+        .esdt_roles(
+            token.eval_to_expr().as_str(),
+            vec![String::from_utf8(EsdtLocalRole::Burn.as_role_name().to_vec()).unwrap()],
+        );
+    account.storage.insert(
+        BytesKey::from("str:repair_streak_payment"),
+        BytesValue::from(format!("nested:{}|u64:0|biguint:1", token.eval_to_expr()).as_str()),
+    );
+
+    world
+        .set_state_step(SetStateStep::new().put_account(contract.eval_to_expr().as_str(), account));
+}
+
+/// A log expected from a transaction, every value given as a scenario expression.
+pub struct ExpectedLog {
+    pub address: String,
+    pub endpoint: &'static str,
+    pub topics: Vec<String>,
+    pub data: Vec<String>,
+}
+
+impl ExpectedLog {
+    fn to_check_log(&self) -> CheckLog {
+        let context = InterpreterContext::default();
+        let expr = |expr: &str| CheckValue::Equal(BytesValue::interpret_from(expr, &context));
+
+        CheckLog {
+            address: expr(&self.address),
+            endpoint: expr(&format!("str:{}", self.endpoint)),
+            topics: CheckValue::Equal(self.topics.iter().map(|topic| expr(topic)).collect()),
+            data: CheckValue::Equal(self.data.iter().map(|data| expr(data)).collect()),
+        }
+    }
+}
+
+/// Verifies that the transaction emits exactly the given logs, in order.
+///
+/// The check is also written to the scenario trace, so the Go VM runs it too.
+pub struct ExpectLogs(pub Vec<ExpectedLog>);
+
+impl<Env, Original> RHListItem<Env, Original> for ExpectLogs
+where
+    Env: TxEnv,
+{
+    type Returns = ();
+}
+
+impl<Env, Original> RHListItemExec<TxResponse, Env, Original> for ExpectLogs
+where
+    Env: TxEnv<RHExpect = TxExpect>,
+{
+    fn item_tx_expect(&self, mut prev: TxExpect) -> TxExpect {
+        prev.logs = CheckLogs::List(CheckLogList {
+            list: self.0.iter().map(ExpectedLog::to_check_log).collect(),
+            more_allowed_at_end: false,
+        });
+        // Otherwise the expectation is replaced by the actual response in the trace, which has no logs
+        prev.build_from_response = false;
+        prev
+    }
+
+    fn item_process_result(self, _: &TxResponse) -> Self::Returns {}
+}
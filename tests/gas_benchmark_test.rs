@@ -0,0 +1,310 @@
+//! Gas benchmarks of the endpoints, run with the Go VM.
+//!
+//! Only built with `--features gas-benchmarks`, since they need `mx-scenario-go` on the `PATH`
+//! and the contract built in `output/`.
+#![cfg(feature = "gas-benchmarks")]
+
+use multiversx_sc_scenario::imports::*;
+use std::{collections::BTreeMap, path::Path, process::Command};
+
+mod common;
+mod proxy;
+
+const OWNER_ADDRESS: TestAddress =
+    TestAddress::new("erd14pytqekzvghdl9frcdu9pyt2c34wjtw4hq4aequntmp54whfdzsqsg7hft");
+const SC_ADDRESS: TestSCAddress =
+    TestSCAddress::new("erd1qqqqqqqqqqqqqpgqrhzm5tlnqgyxmc0suqfcfwzn8fxcfdg4dzsqysc3tt");
+// Relative to the gas scenarios folder, where the benchmarks are written
+const CODE_PATH: MxscPath = MxscPath::new("../../output/on-chain-claim.mxsc.json");
+const TOKEN: TestTokenIdentifier = TestTokenIdentifier::new("VLAD-6bde05");
+
+const GAS_SCENARIOS_DIR: &str = "scenarios/gas";
+const BASELINE_PATH: &str = "scenarios/gas/baseline.json";
+const SETUP_GAS: u64 = 30_000_000;
+const BATCH_SIZE: u8 = 10;
+const TIMELOCK_EPOCHS: u64 = on_chain_claim::timelock::DEFAULT_TIMELOCK_EPOCHS;
+
+/// An endpoint call measured in isolation: `setup` brings the contract in the desired state,
+/// then every transaction sent by `measure` gets the benchmarked gas limit.
+struct Benchmark {
+    name: &'static str,
+    setup: fn(&mut ScenarioWorld),
+    measure: fn(&mut ScenarioWorld, u64),
+}
+
+const BENCHMARKS: &[Benchmark] = &[
+    Benchmark {
+        name: "claim_first",
+        setup: deploy,
+        measure: claim,
+    },
+    Benchmark {
+        name: "claim_consecutive",
+        setup: |world| {
+            deploy(world);
+            claim(world, SETUP_GAS);
+            world.current_block().block_epoch(2);
+        },
+        measure: claim,
+    },
+    Benchmark {
+        name: "claim_after_missed_epochs",
+        setup: |world| {
+            deploy(world);
+            claim(world, SETUP_GAS);
+            world.current_block().block_epoch(4);
+        },
+        measure: claim,
+    },
+    Benchmark {
+        name: "claim_and_repair",
+        setup: |world| {
+            setup_contract_with_burn_role(world);
+            claim(world, SETUP_GAS);
+            world.current_block().block_epoch(4);
+        },
+        measure: |world, gas| {
+            world
+                .tx()
+                .from(OWNER_ADDRESS)
+                .to(SC_ADDRESS)
+                .gas(gas)
+                .typed(proxy::OnChainClaimContractProxy)
+                .claim_and_repair()
+                .payment((TokenIdentifier::from(TOKEN), 0, BigUint::from(1u64)))
+                .returns(ReturnsResult)
+                .run();
+        },
+    },
+    Benchmark {
+        name: "update_state",
+        setup: deploy,
+        measure: |world, gas| update_state(world, gas, OWNER_ADDRESS.to_address()),
+    },
+    Benchmark {
+        name: "update_state_batch",
+        setup: deploy,
+        measure: |world, gas| {
+            for index in 0..BATCH_SIZE {
+                update_state(world, gas, same_shard_address(index));
+            }
+        },
+    },
+    Benchmark {
+        name: "propose_config_change",
+        setup: deploy,
+        measure: propose_repair_streak_payment,
+    },
+    Benchmark {
+        name: "execute_config_change",
+        setup: |world| {
+            deploy(world);
+            propose_repair_streak_payment(world, SETUP_GAS);
+            world.current_block().block_epoch(1 + TIMELOCK_EPOCHS);
+        },
+        measure: |world, gas| {
+            world
+                .tx()
+                .from(OWNER_ADDRESS)
+                .to(SC_ADDRESS)
+                .gas(gas)
+                .typed(proxy::OnChainClaimContractProxy)
+                .execute_config_change(1u64)
+                .returns(ReturnsResult)
+                .run();
+        },
+    },
+];
+
+/// Gas used by each benchmark, as measured by the Go VM, and the tolerated increase.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Baseline {
+    threshold_percent: u64,
+    gas_used: BTreeMap<String, u64>,
+}
+
+impl Baseline {
+    fn load() -> Self {
+        let content = std::fs::read_to_string(BASELINE_PATH).expect("gas baseline not found");
+        serde_json::from_str(&content).expect("invalid gas baseline")
+    }
+
+    fn save(&self) {
+        let content = serde_json::to_string_pretty(self).unwrap();
+        std::fs::write(BASELINE_PATH, content + "\n").expect("failed to write gas baseline");
+    }
+
+    fn gas_limit(&self, gas_used: u64) -> u64 {
+        gas_used + gas_used * self.threshold_percent / 100
+    }
+}
+
+fn world() -> ScenarioWorld {
+    let mut world = ScenarioWorld::new();
+    world.start_trace();
+
+    world
+        .account(OWNER_ADDRESS)
+        .balance(100)
+        .esdt_balance(TOKEN, 100);
+    world.current_block().block_epoch(1);
+
+    world.register_contract(CODE_PATH, on_chain_claim::ContractBuilder);
+    world
+}
+
+fn deploy(world: &mut ScenarioWorld) {
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .gas(SETUP_GAS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .new_address(SC_ADDRESS)
+        .run();
+}
+
+fn setup_contract_with_burn_role(world: &mut ScenarioWorld) {
+    common::setup_contract_with_burn_role(world, CODE_PATH, OWNER_ADDRESS, SC_ADDRESS, TOKEN);
+}
+
+fn claim(world: &mut ScenarioWorld, gas: u64) {
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .gas(gas)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim()
+        .returns(ReturnsResult)
+        .run();
+}
+
+fn update_state(world: &mut ScenarioWorld, gas: u64, address: Address) {
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .gas(gas)
+        .typed(proxy::OnChainClaimContractProxy)
+        .update_state(address, 1u64, 2u64, 20u64, 40u64)
+        .returns(ReturnsResult)
+        .run();
+}
+
+fn propose_repair_streak_payment(world: &mut ScenarioWorld, gas: u64) {
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .gas(gas)
+        .typed(proxy::OnChainClaimContractProxy)
+        .propose_config_change(proxy::ConfigChange::SetRepairStreakPayment {
+            token_identifier: TOKEN.into(),
+            token_nonce: 0,
+        })
+        .returns(ReturnsResult)
+        .run();
+}
+
+/// A distinct address in the owner's shard, which only depends on the last byte.
+fn same_shard_address(index: u8) -> Address {
+    let mut bytes = *OWNER_ADDRESS.to_address().as_array();
+    bytes[0] = index;
+    Address::from(bytes)
+}
+
+fn scenario_path(name: &str) -> String {
+    format!("{GAS_SCENARIOS_DIR}/{name}.scen.json")
+}
+
+fn write_benchmark_scenario(benchmark: &Benchmark, gas_limit: u64, path: &str) {
+    let mut world = world();
+    (benchmark.setup)(&mut world);
+    (benchmark.measure)(&mut world, gas_limit);
+    world.write_scenario_trace(path);
+}
+
+/// Runs a scenario with the Go VM, without failing the test when the scenario fails.
+/// Panics when `mx-scenario-go` can't be run, so that a missing tool is never mistaken for a pass.
+fn go_vm_accepts(path: &str) -> bool {
+    let absolute_path = Path::new(path).canonicalize().unwrap();
+    Command::new("mx-scenario-go")
+        .arg("run")
+        .arg(absolute_path)
+        .output()
+        .expect("mx-scenario-go not found, it must be on the PATH")
+        .status
+        .success()
+}
+
+/// Smallest gas limit with which the benchmark succeeds in the Go VM, which is the gas it uses.
+fn measure_gas_used(benchmark: &Benchmark) -> u64 {
+    let path = scenario_path(&format!("{}.probe", benchmark.name));
+    let (mut failing, mut passing) = (0u64, SETUP_GAS);
+
+    write_benchmark_scenario(benchmark, passing, &path);
+    assert!(
+        go_vm_accepts(&path),
+        "{} fails even with {SETUP_GAS} gas",
+        benchmark.name
+    );
+    while passing - failing > 1 {
+        let gas_limit = failing + (passing - failing) / 2;
+        write_benchmark_scenario(benchmark, gas_limit, &path);
+        if go_vm_accepts(&path) {
+            passing = gas_limit;
+        } else {
+            failing = gas_limit;
+        }
+    }
+
+    std::fs::remove_file(path).unwrap();
+    passing
+}
+
+/// Fails when an endpoint uses more gas than its baseline plus the threshold, since it then runs out of gas.
+#[test]
+fn gas_benchmarks_go() {
+    let baseline = Baseline::load();
+
+    for benchmark in BENCHMARKS {
+        let gas_used = baseline
+            .gas_used
+            .get(benchmark.name)
+            .copied()
+            .unwrap_or_else(|| {
+                panic!(
+                    "no gas baseline recorded for {}, run record_gas_baseline",
+                    benchmark.name
+                )
+            });
+
+        let path = scenario_path(benchmark.name);
+        write_benchmark_scenario(benchmark, baseline.gas_limit(gas_used), &path);
+        assert!(
+            go_vm_accepts(&path),
+            "{} uses more than {gas_used} gas plus {}%, see {path}",
+            benchmark.name,
+            baseline.threshold_percent
+        );
+    }
+}
+
+/// Measures every benchmark with the Go VM and records the results as the new baseline.
+#[test]
+#[ignore = "rewrites the gas baseline"]
+fn record_gas_baseline() {
+    let mut baseline = Baseline::load();
+
+    for benchmark in BENCHMARKS {
+        let gas_used = measure_gas_used(benchmark);
+        println!("{}: {gas_used}", benchmark.name);
+        baseline
+            .gas_used
+            .insert(benchmark.name.to_string(), gas_used);
+    }
+
+    baseline.save();
+}
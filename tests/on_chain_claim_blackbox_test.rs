@@ -1,19 +1,36 @@
-use multiversx_sc_scenario::imports::*;
+use multiversx_sc_scenario::{
+    imports::*,
+    multiversx_chain_vm::crypto_functions::keccak256,
+    scenario_format::interpret_trait::{InterpretableFrom, InterpreterContext},
+    scenario_model::{Account, BytesKey, BytesValue, CheckAccount, CheckStateStep, CheckStorage},
+};
+use multiversx_sc_snippets::sdk::crypto::{private_key::PrivateKey, public_key::PublicKey};
 
+use on_chain_claim::{access_proxy, config::MAX_ALLOWED_REPAIR_GAP, timelock::MAX_TIMELOCK_EPOCHS};
+
+mod common;
 mod proxy;
 
+use common::{ExpectLogs, ExpectedLog};
+
 const OWNER_ADDRESS: TestAddress =
     TestAddress::new("erd14pytqekzvghdl9frcdu9pyt2c34wjtw4hq4aequntmp54whfdzsqsg7hft");
 const SECOND_USER: TestAddress =
     TestAddress::new("erd18tl5dm72ppkzmx5kvxjlnclrd7wa349r2ytutx60ugqhq5gnl66s5046zd");
 const SC_ADDRESS: TestSCAddress =
     TestSCAddress::new("erd1qqqqqqqqqqqqqpgqrhzm5tlnqgyxmc0suqfcfwzn8fxcfdg4dzsqysc3tt");
-const CODE_PATH: MxscPath = MxscPath::new("output/on-chain-claim.mxsc.json");
+const USER_SC_ADDRESS: TestSCAddress = TestSCAddress::new("user-sc");
+// Relative to the scenarios folder, where the traces are written
+const CODE_PATH: MxscPath = MxscPath::new("../output/on-chain-claim.mxsc.json");
 const TOKEN: TestTokenIdentifier = TestTokenIdentifier::new("VLAD-6bde05");
 const INVALID_TOKEN: TestTokenIdentifier = TestTokenIdentifier::new("12sasdf");
+const TIMELOCK_EPOCHS: u64 = on_chain_claim::timelock::DEFAULT_TIMELOCK_EPOCHS;
+// Arbitrary ed25519 key of a user that signs claims instead of sending them
+const SIGNER_PRIVATE_KEY: &str = "e253a571ca153dc2aee845819f74bcc9773b0586edead15a94cb7235a5027436";
 
 fn world() -> ScenarioWorld {
     let mut blockchain = ScenarioWorld::new();
+    blockchain.start_trace();
 
     blockchain
         .account(OWNER_ADDRESS)
@@ -23,16 +40,110 @@ fn world() -> ScenarioWorld {
         .account(SECOND_USER)
         .balance(1000)
         .esdt_balance(TOKEN, 1000);
+    blockchain
+        .account(USER_SC_ADDRESS)
+        .code(CODE_PATH)
+        .balance(1000)
+        .esdt_balance(TOKEN, 1000);
     blockchain.current_block().block_epoch(1);
 
     blockchain.register_contract(CODE_PATH, on_chain_claim::ContractBuilder);
     blockchain
 }
 
+fn setup_contract_with_burn_role(world: &mut ScenarioWorld) {
+    common::setup_contract_with_burn_role(world, CODE_PATH, OWNER_ADDRESS, SC_ADDRESS, TOKEN);
+}
+
+/// A log of the contract, emitted while executing `endpoint`.
+fn contract_log(endpoint: &'static str, topics: Vec<String>, data: String) -> ExpectedLog {
+    ExpectedLog {
+        address: SC_ADDRESS.eval_to_expr(),
+        endpoint,
+        topics,
+        data: vec![data],
+    }
+}
+
+/// Nested encoding of an `AddressInfo`, given as current streak, last epoch claimed, total epochs claimed and best streak.
+fn address_info_expr(
+    [current_streak, last_epoch_claimed, total_epochs_claimed, best_streak]: [u64; 4],
+) -> String {
+    format!("u64:{current_streak}|u64:{last_epoch_claimed}|u64:{total_epochs_claimed}|u64:{best_streak}")
+}
+
+fn address_info_update_expr(previous: [u64; 4], current: [u64; 4]) -> String {
+    format!(
+        "{}|{}",
+        address_info_expr(previous),
+        address_info_expr(current)
+    )
+}
+
+fn new_claim_log(epoch: u64, previous: [u64; 4], current: [u64; 4]) -> ExpectedLog {
+    contract_log(
+        "claim",
+        vec![
+            "str:new_claim".to_string(),
+            OWNER_ADDRESS.eval_to_expr(),
+            epoch.to_string(),
+        ],
+        address_info_update_expr(previous, current),
+    )
+}
+
+/// The `admin_action` log of an action made by the owner, `action` being the encoded `AdminAction`.
+fn admin_action_log(
+    endpoint: &'static str,
+    action_id: u64,
+    epoch: u64,
+    action: String,
+) -> ExpectedLog {
+    admin_action_log_from(
+        endpoint,
+        action_id,
+        OWNER_ADDRESS.eval_to_expr(),
+        epoch,
+        action,
+    )
+}
+
+fn admin_action_log_from(
+    endpoint: &'static str,
+    action_id: u64,
+    admin: String,
+    epoch: u64,
+    action: String,
+) -> ExpectedLog {
+    contract_log(
+        endpoint,
+        vec![
+            "str:admin_action".to_string(),
+            action_id.to_string(),
+            admin,
+            epoch.to_string(),
+        ],
+        action,
+    )
+}
+
+fn config_change_executed_log(id: u64, epoch: u64) -> ExpectedLog {
+    ExpectedLog {
+        address: SC_ADDRESS.eval_to_expr(),
+        endpoint: "executeConfigChange",
+        topics: vec![
+            "str:config_change_executed".to_string(),
+            id.to_string(),
+            OWNER_ADDRESS.eval_to_expr(),
+            epoch.to_string(),
+        ],
+        data: vec!["".to_string()],
+    }
+}
+
 #[test]
 fn on_chain_claim_blackbox_init() {
     let mut world = world();
-    world.start_trace();
 
     let new_address = world
         .tx()
@@ -47,13 +158,12 @@ fn on_chain_claim_blackbox_init() {
 
     assert_eq!(new_address, SC_ADDRESS);
 
-    world.write_scenario_trace("scenarios/trace1.scen.json");
+    world.write_scenario_trace("scenarios/init.scen.json");
 }
 
 #[test]
 fn on_chain_claim_blackbox_init_invalid_token_id() {
     let mut world = world();
-    world.start_trace();
 
     world
         .tx()
@@ -66,13 +176,12 @@ fn on_chain_claim_blackbox_init_invalid_token_id() {
         .new_address(SC_ADDRESS)
         .run();
 
-    world.write_scenario_trace("scenarios/trace9.json");
+    world.write_scenario_trace("scenarios/init_invalid_token_id.scen.json");
 }
 
 #[test]
 fn on_chain_claim_double_claim() {
     let mut world = world();
-    world.start_trace();
 
     let new_address = world
         .tx()
@@ -105,13 +214,12 @@ fn on_chain_claim_double_claim() {
         .returns(ExpectError(4, "epoch already claimed"))
         .run();
 
-    world.write_scenario_trace("scenarios/trace10.json");
+    world.write_scenario_trace("scenarios/claim_twice_same_epoch.scen.json");
 }
 
 #[test]
 fn on_chain_claim_late_claim() {
     let mut world = world();
-    world.start_trace();
 
     let new_address = world
         .tx()
@@ -159,13 +267,12 @@ fn on_chain_claim_late_claim() {
     assert_eq!(address_info.last_epoch_claimed, 3);
     assert_eq!(address_info.total_epochs_claimed, 2);
 
-    world.write_scenario_trace("scenarios/trace11.json");
+    world.write_scenario_trace("scenarios/claim_after_missed_epoch.scen.json");
 }
 
 #[test]
 fn on_chain_claim_wrong_shard_claim() {
     let mut world = world();
-    world.start_trace();
 
     let new_address = world
         .tx()
@@ -189,13 +296,12 @@ fn on_chain_claim_wrong_shard_claim() {
         .returns(ExpectError(4, "wrong shard"))
         .run();
 
-    world.write_scenario_trace("scenarios/trace12.json");
+    world.write_scenario_trace("scenarios/claim_wrong_shard.scen.json");
 }
 
 #[test]
 fn on_chain_claim_claim_happy_path() {
     let mut world = world();
-    world.start_trace();
 
     let new_address = world
         .tx()
@@ -216,7 +322,11 @@ fn on_chain_claim_claim_happy_path() {
         .to(SC_ADDRESS)
         .typed(proxy::OnChainClaimContractProxy)
         .claim()
-        .returns(ReturnsResult)
+        .returns(ExpectLogs(vec![new_claim_log(
+            1,
+            [0, 0, 0, 0],
+            [1, 1, 1, 1],
+        )]))
         .run();
 
     world.current_block().block_epoch(2);
@@ -227,7 +337,11 @@ fn on_chain_claim_claim_happy_path() {
         .to(SC_ADDRESS)
         .typed(proxy::OnChainClaimContractProxy)
         .claim()
-        .returns(ReturnsResult)
+        .returns(ExpectLogs(vec![new_claim_log(
+            2,
+            [1, 1, 1, 1],
+            [2, 2, 2, 2],
+        )]))
         .run();
 
     let address_info = world
@@ -243,13 +357,12 @@ fn on_chain_claim_claim_happy_path() {
     assert_eq!(address_info.last_epoch_claimed, 2);
     assert_eq!(address_info.total_epochs_claimed, 2);
 
-    world.write_scenario_trace("scenarios/trace12.json");
+    world.write_scenario_trace("scenarios/claim.scen.json");
 }
 
 #[test]
 fn on_chain_claim_claim_and_repair_bad_amount() {
     let mut world = world();
-    world.start_trace();
 
     let new_address = world
         .tx()
@@ -278,13 +391,12 @@ fn on_chain_claim_claim_and_repair_bad_amount() {
         .returns(ExpectError(4, "Bad payment token/amount"))
         .run();
 
-    world.write_scenario_trace("scenarios/trace2.scen.json");
+    world.write_scenario_trace("scenarios/claim_and_repair_bad_amount.scen.json");
 }
 
 #[test]
 fn on_chain_claim_claim_and_repair_wrong_shard() {
     let mut world = world();
-    world.start_trace();
 
     let new_address = world
         .tx()
@@ -313,13 +425,12 @@ fn on_chain_claim_claim_and_repair_wrong_shard() {
         .returns(ExpectError(4, "wrong shard"))
         .run();
 
-    world.write_scenario_trace("scenarios/trace3.scen.json");
+    world.write_scenario_trace("scenarios/claim_and_repair_wrong_shard.scen.json");
 }
 
 #[test]
 fn on_chain_claim_claim_and_repair_non_burnable_token() {
     let mut world = world();
-    world.start_trace();
 
     let new_address = world
         .tx()
@@ -359,26 +470,14 @@ fn on_chain_claim_claim_and_repair_non_burnable_token() {
         .returns(ExpectError(10, "action is not allowed"))
         .run();
 
-    world.write_scenario_trace("scenarios/trace5.scen.json");
+    world.write_scenario_trace("scenarios/claim_and_repair_non_burnable_token.scen.json");
 }
 
 #[test]
 fn on_chain_claim_claim_and_repair_happy_path() {
     let mut world = world();
-    world.start_trace();
-
-    let new_address = world
-        .tx()
-        .from(OWNER_ADDRESS)
-        .typed(proxy::OnChainClaimContractProxy)
-        .init(TOKEN, 0u64)
-        .code(CODE_PATH)
-        .code_metadata(CodeMetadata::PAYABLE)
-        .returns(ReturnsNewAddress)
-        .new_address(SC_ADDRESS)
-        .run();
 
-    assert_eq!(new_address, SC_ADDRESS);
+    setup_contract_with_burn_role(&mut world);
 
     world
         .tx()
@@ -386,13 +485,16 @@ fn on_chain_claim_claim_and_repair_happy_path() {
         .to(SC_ADDRESS)
         .typed(proxy::OnChainClaimContractProxy)
         .claim()
-        .returns(ReturnsResult)
+        .returns(ExpectLogs(vec![new_claim_log(
+            1,
+            [0, 0, 0, 0],
+            [1, 1, 1, 1],
+        )]))
         .run();
 
     world.current_block().block_epoch(4);
-    world.set_esdt_local_roles(OWNER_ADDRESS, b"VLAD-6bde05", &[EsdtLocalRole::Burn]);
-    world.set_esdt_local_roles(SC_ADDRESS, b"VLAD-6bde05", &[EsdtLocalRole::Burn]);
 
+    // epochs 2 and 3 are repaired, the payment is burnt
     world
         .tx()
         .from(OWNER_ADDRESS)
@@ -404,7 +506,29 @@ fn on_chain_claim_claim_and_repair_happy_path() {
             0,
             BigUint::<StaticApi>::from(1u128),
         ))
-        .returns(ReturnsResult)
+        .returns(ExpectLogs(vec![
+            contract_log(
+                "claimAndRepair",
+                vec![
+                    "str:new_claim_and_repair".to_string(),
+                    OWNER_ADDRESS.eval_to_expr(),
+                    "4".to_string(),
+                    "2".to_string(),
+                    "nested:str:VLAD-6bde05|u64:0|biguint:1".to_string(),
+                ],
+                address_info_update_expr([1, 1, 1, 1], [4, 4, 4, 4]),
+            ),
+            ExpectedLog {
+                address: SC_ADDRESS.eval_to_expr(),
+                endpoint: "ESDTLocalBurn",
+                topics: vec![
+                    "str:VLAD-6bde05".to_string(),
+                    "0".to_string(),
+                    "1".to_string(),
+                ],
+                data: Vec::new(),
+            },
+        ]))
         .run();
 
     let address_info = world
@@ -417,13 +541,12 @@ fn on_chain_claim_claim_and_repair_happy_path() {
 
     assert_ne!(address_info.best_streak, 0);
 
-    world.write_scenario_trace("scenarios/trace6.scen.json");
+    world.write_scenario_trace("scenarios/claim_and_repair.scen.json");
 }
 
 #[test]
 fn on_chain_claim_update_state_happy_path() {
     let mut world = world();
-    world.start_trace();
 
     let new_address = world
         .tx()
@@ -450,7 +573,29 @@ fn on_chain_claim_update_state_happy_path() {
             20u64,
             40u64,
         )
-        .returns(ReturnsResult)
+        .returns(ExpectLogs(vec![
+            contract_log(
+                "updateState",
+                vec![
+                    "str:new_update_state".to_string(),
+                    OWNER_ADDRESS.eval_to_expr(),
+                    "1".to_string(),
+                ],
+                address_info_update_expr([0, 0, 0, 0], [1, 2, 20, 40]),
+            ),
+            // the init audited the 4 roles granted to the deployer
+            admin_action_log(
+                "updateState",
+                5,
+                1,
+                format!(
+                    "u8:0|{}|{}|{}",
+                    OWNER_ADDRESS.eval_to_expr(),
+                    address_info_expr([0, 0, 0, 0]),
+                    address_info_expr([1, 2, 20, 40])
+                ),
+            ),
+        ]))
         .run();
 
     let address_info = world
@@ -466,13 +611,12 @@ fn on_chain_claim_update_state_happy_path() {
     assert_eq!(address_info.last_epoch_claimed, 2);
     assert_eq!(address_info.total_epochs_claimed, 20);
 
-    world.write_scenario_trace("scenarios/trace7.scen.json");
+    world.write_scenario_trace("scenarios/update_state.scen.json");
 }
 
 #[test]
 fn on_chain_claim_update_state_non_admin() {
     let mut world = world();
-    world.start_trace();
 
     let new_address = world
         .tx()
@@ -499,16 +643,15 @@ fn on_chain_claim_update_state_non_admin() {
             20u64,
             40u64,
         )
-        .returns(ExpectError(4, "Endpoint can only be called by admins"))
+        .returns(ExpectError(4, "Caller doesn't have the required role"))
         .run();
 
-    world.write_scenario_trace("scenarios/trace8.scen.json");
+    world.write_scenario_trace("scenarios/update_state_non_admin.scen.json");
 }
 
 #[test]
 fn on_chain_claim_update_state_wrong_shard() {
     let mut world = world();
-    world.start_trace();
 
     let new_address = world
         .tx()
@@ -538,5 +681,2948 @@ fn on_chain_claim_update_state_wrong_shard() {
         .returns(ExpectError(4, "wrong shard"))
         .run();
 
-    world.write_scenario_trace("scenarios/trace13.scen.json");
+    world.write_scenario_trace("scenarios/update_state_wrong_shard.scen.json");
+}
+
+#[test]
+fn on_chain_claim_admin_action_count() {
+    let mut world = world();
+
+    let new_address = world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .returns(ReturnsNewAddress)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    assert_eq!(new_address, SC_ADDRESS);
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .update_state(
+            ManagedAddress::from_address(&OWNER_ADDRESS.to_address()),
+            1u64,
+            2u64,
+            20u64,
+            40u64,
+        )
+        .returns(ReturnsResult)
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .pause()
+        .returns(ReturnsResult)
+        .run();
+
+    let admin_action_count = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .admin_action_count()
+        .returns(ReturnsResult)
+        .run();
+
+    // the init grants every role to the deployer, which is audited as well
+    assert_eq!(admin_action_count, 6);
+
+    world.write_scenario_trace("scenarios/admin_action_count.scen.json");
+}
+
+#[test]
+fn on_chain_claim_claim_from_sc() {
+    let mut world = world();
+
+    let new_address = world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .returns(ReturnsNewAddress)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    assert_eq!(new_address, SC_ADDRESS);
+
+    world
+        .tx()
+        .from(USER_SC_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim()
+        .returns(ExpectError(4, "Only user accounts can perform claim"))
+        .run();
+
+    world.write_scenario_trace("scenarios/claim_from_sc.scen.json");
+}
+
+#[test]
+fn on_chain_claim_claim_and_repair_without_claim() {
+    let mut world = world();
+
+    let new_address = world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .returns(ReturnsNewAddress)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    assert_eq!(new_address, SC_ADDRESS);
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_and_repair()
+        .payment((
+            TokenIdentifier::from(TOKEN),
+            0,
+            BigUint::<StaticApi>::from(1u128),
+        ))
+        .returns(ExpectError(4, "can't repair streak for address"))
+        .run();
+
+    world.write_scenario_trace("scenarios/claim_and_repair_without_claim.scen.json");
+}
+
+#[test]
+fn on_chain_claim_claim_and_repair_from_sc() {
+    let mut world = world();
+
+    let new_address = world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .returns(ReturnsNewAddress)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    assert_eq!(new_address, SC_ADDRESS);
+
+    world
+        .tx()
+        .from(USER_SC_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_and_repair()
+        .payment((
+            TokenIdentifier::from(TOKEN),
+            0,
+            BigUint::<StaticApi>::from(1u128),
+        ))
+        .returns(ExpectError(
+            4,
+            "Only user accounts can perform claim and repair",
+        ))
+        .run();
+
+    world.write_scenario_trace("scenarios/claim_and_repair_from_sc.scen.json");
+}
+
+#[test]
+fn on_chain_claim_claim_and_repair_after_7_missed_epochs() {
+    let mut world = world();
+
+    let new_address = world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .returns(ReturnsNewAddress)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    assert_eq!(new_address, SC_ADDRESS);
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim()
+        .returns(ReturnsResult)
+        .run();
+
+    world.current_block().block_epoch(9);
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_and_repair()
+        .payment((
+            TokenIdentifier::from(TOKEN),
+            0,
+            BigUint::<StaticApi>::from(1u128),
+        ))
+        .returns(ExpectError(4, "can't repair streak for current epoch"))
+        .run();
+
+    world.write_scenario_trace("scenarios/claim_and_repair_after_7_missed_epochs.scen.json");
+}
+
+#[test]
+fn on_chain_claim_claim_and_repair_after_3_missed_epochs() {
+    let mut world = world();
+
+    setup_contract_with_burn_role(&mut world);
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim()
+        .returns(ReturnsResult)
+        .run();
+
+    world.current_block().block_epoch(5);
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_and_repair()
+        .payment((
+            TokenIdentifier::from(TOKEN),
+            0,
+            BigUint::<StaticApi>::from(1u128),
+        ))
+        .returns(ReturnsResult)
+        .run();
+
+    let address_info = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_address_info(OWNER_ADDRESS)
+        .returns(ReturnsResult)
+        .run();
+
+    assert_eq!(address_info.best_streak, 5);
+    assert_eq!(address_info.current_streak, 5);
+    assert_eq!(address_info.last_epoch_claimed, 5);
+    assert_eq!(address_info.total_epochs_claimed, 5);
+
+    world.write_scenario_trace("scenarios/claim_and_repair_after_3_missed_epochs.scen.json");
+}
+
+#[test]
+fn on_chain_claim_claim_and_repair_twice_same_epoch() {
+    let mut world = world();
+
+    setup_contract_with_burn_role(&mut world);
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim()
+        .returns(ReturnsResult)
+        .run();
+
+    world.current_block().block_epoch(4);
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_and_repair()
+        .payment((
+            TokenIdentifier::from(TOKEN),
+            0,
+            BigUint::<StaticApi>::from(1u128),
+        ))
+        .returns(ReturnsResult)
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_and_repair()
+        .payment((
+            TokenIdentifier::from(TOKEN),
+            0,
+            BigUint::<StaticApi>::from(1u128),
+        ))
+        .returns(ExpectError(4, "can't repair streak for current epoch"))
+        .run();
+
+    world.write_scenario_trace("scenarios/claim_and_repair_twice_same_epoch.scen.json");
+}
+
+#[test]
+fn on_chain_claim_update_state_weird_data() {
+    let mut world = world();
+
+    let new_address = world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .returns(ReturnsNewAddress)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    assert_eq!(new_address, SC_ADDRESS);
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .raw_call("updateState")
+        .argument(&"not-an-address")
+        .argument(&1u64)
+        .argument(&2u64)
+        .argument(&20u64)
+        .argument(&40u64)
+        .returns(ExpectError(
+            4,
+            "argument decode error (address): bad array length",
+        ))
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .raw_call("updateState")
+        .argument(&OWNER_ADDRESS)
+        .argument(&1u64)
+        .returns(ExpectError(4, "wrong number of arguments"))
+        .run();
+
+    world.write_scenario_trace("scenarios/update_state_weird_data.scen.json");
+}
+
+fn claim_at_epoch(world: &mut ScenarioWorld, epoch: u64) {
+    world.current_block().block_epoch(epoch);
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim()
+        .returns(ReturnsResult)
+        .run();
+}
+
+fn season_stats(world: &mut ScenarioWorld, season_id: u64) -> proxy::SeasonStats {
+    world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_season_stats(OWNER_ADDRESS, season_id)
+        .returns(ReturnsResult)
+        .run()
+}
+
+#[test]
+fn on_chain_claim_season_claims() {
+    let mut world = world();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    let season_id = world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .open_season(ManagedBuffer::from("Spring"), 2u64, 5u64)
+        .returns(ReturnsResult)
+        .run();
+    assert_eq!(season_id, 1);
+
+    // claims before the season only count for the lifetime streak
+    claim_at_epoch(&mut world, 1);
+    claim_at_epoch(&mut world, 2);
+    claim_at_epoch(&mut world, 3);
+
+    let current_season = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_current_season()
+        .returns(ReturnsResult)
+        .run()
+        .into_option()
+        .unwrap();
+    assert_eq!(current_season.id, 1);
+    assert_eq!(current_season.name, ManagedBuffer::from("Spring"));
+
+    let stats = season_stats(&mut world, 1);
+    assert_eq!(stats.season_id, 1);
+    assert_eq!(stats.current_streak, 2);
+    assert_eq!(stats.last_epoch_claimed, 3);
+    assert_eq!(stats.epochs_claimed, 2);
+    assert_eq!(stats.best_streak, 2);
+
+    let address_info = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_address_info(OWNER_ADDRESS)
+        .returns(ReturnsResult)
+        .run();
+    assert_eq!(address_info.current_streak, 3);
+    assert_eq!(address_info.total_epochs_claimed, 3);
+
+    world.write_scenario_trace("scenarios/season_claims.scen.json");
+}
+
+#[test]
+fn on_chain_claim_season_rollover() {
+    let mut world = world();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    for (name, start_epoch, end_epoch) in [("Spring", 1u64, 3u64), ("Summer", 4, 10)] {
+        world
+            .tx()
+            .from(OWNER_ADDRESS)
+            .to(SC_ADDRESS)
+            .typed(proxy::OnChainClaimContractProxy)
+            .open_season(ManagedBuffer::from(name), start_epoch, end_epoch)
+            .returns(ReturnsResult)
+            .run();
+    }
+
+    claim_at_epoch(&mut world, 1);
+    claim_at_epoch(&mut world, 2);
+    claim_at_epoch(&mut world, 3);
+    claim_at_epoch(&mut world, 4);
+
+    let spring_stats = season_stats(&mut world, 1);
+    assert_eq!(spring_stats.current_streak, 3);
+    assert_eq!(spring_stats.epochs_claimed, 3);
+    assert_eq!(spring_stats.best_streak, 3);
+
+    let summer_stats = season_stats(&mut world, 2);
+    assert_eq!(summer_stats.season_id, 2);
+    assert_eq!(summer_stats.current_streak, 1);
+    assert_eq!(summer_stats.epochs_claimed, 1);
+    assert_eq!(summer_stats.best_streak, 1);
+
+    let address_info = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_address_info(OWNER_ADDRESS)
+        .returns(ReturnsResult)
+        .run();
+    assert_eq!(address_info.current_streak, 4);
+    assert_eq!(address_info.best_streak, 4);
+
+    let seasons = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_seasons()
+        .returns(ReturnsResult)
+        .run()
+        .into_iter()
+        .collect::<Vec<_>>();
+    assert_eq!(seasons.len(), 2);
+    assert_eq!(seasons[1].name, ManagedBuffer::from("Summer"));
+
+    world.write_scenario_trace("scenarios/season_rollover.scen.json");
+}
+
+#[test]
+fn on_chain_claim_close_season() {
+    let mut world = world();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .open_season(ManagedBuffer::from("Spring"), 1u64, 10u64)
+        .returns(ReturnsResult)
+        .run();
+
+    claim_at_epoch(&mut world, 1);
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .close_season(1u64)
+        .returns(ReturnsResult)
+        .run();
+    claim_at_epoch(&mut world, 2);
+
+    let stats = season_stats(&mut world, 1);
+    assert_eq!(stats.epochs_claimed, 1);
+    assert_eq!(stats.last_epoch_claimed, 1);
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .close_season(1u64)
+        .returns(ExpectError(4, "Season is not ongoing"))
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .close_season(2u64)
+        .returns(ExpectError(4, "Unknown season"))
+        .run();
+
+    world.write_scenario_trace("scenarios/close_season.scen.json");
+}
+
+#[test]
+fn on_chain_claim_open_season_invalid() {
+    let mut world = world();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .open_season(ManagedBuffer::from("Spring"), 2u64, 5u64)
+        .returns(ReturnsResult)
+        .run();
+
+    world.current_block().block_epoch(3);
+    for (name, start_epoch, end_epoch, error) in [
+        ("", 10u64, 20u64, "Empty season name"),
+        ("Summer", 20, 10, "Season ends before it starts"),
+        ("Summer", 2, 10, "Season starts in the past"),
+        ("Summer", 5, 10, "Season overlaps the previous one"),
+    ] {
+        world
+            .tx()
+            .from(OWNER_ADDRESS)
+            .to(SC_ADDRESS)
+            .typed(proxy::OnChainClaimContractProxy)
+            .open_season(ManagedBuffer::from(name), start_epoch, end_epoch)
+            .returns(ExpectError(4, error))
+            .run();
+    }
+
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .open_season(ManagedBuffer::from("Summer"), 6u64, 10u64)
+        .returns(ExpectError(4, "Caller doesn't have the required role"))
+        .run();
+
+    world.write_scenario_trace("scenarios/open_season_invalid.scen.json");
+}
+
+#[test]
+fn on_chain_claim_delegated_claim() {
+    let mut world = world();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    world
+        .tx()
+        .from(USER_SC_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_for(OWNER_ADDRESS)
+        .returns(ExpectError(4, "Caller is not an authorized delegate"))
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .authorize_delegate(USER_SC_ADDRESS)
+        .returns(ReturnsResult)
+        .run();
+
+    world
+        .tx()
+        .from(USER_SC_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_for(OWNER_ADDRESS)
+        .returns(ReturnsResult)
+        .run();
+
+    let address_info = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_address_info(OWNER_ADDRESS)
+        .returns(ReturnsResult)
+        .run();
+    assert_eq!(address_info.current_streak, 1);
+    assert_eq!(address_info.last_epoch_claimed, 1);
+
+    let beneficiaries = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .beneficiaries(USER_SC_ADDRESS)
+        .returns(ReturnsResult)
+        .run()
+        .into_iter()
+        .collect::<Vec<_>>();
+    assert_eq!(beneficiaries, [OWNER_ADDRESS.to_managed_address()]);
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim()
+        .returns(ExpectError(4, "epoch already claimed"))
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .revoke_delegate(USER_SC_ADDRESS)
+        .returns(ReturnsResult)
+        .run();
+
+    world.current_block().block_epoch(2);
+    world
+        .tx()
+        .from(USER_SC_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_for(OWNER_ADDRESS)
+        .returns(ExpectError(4, "Caller is not an authorized delegate"))
+        .run();
+
+    let is_delegate = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .is_delegate(OWNER_ADDRESS, USER_SC_ADDRESS)
+        .returns(ReturnsResult)
+        .run();
+    assert!(!is_delegate);
+
+    world.write_scenario_trace("scenarios/delegated_claim.scen.json");
+}
+
+#[test]
+fn on_chain_claim_delegate_invalid() {
+    let mut world = world();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .authorize_delegate(OWNER_ADDRESS)
+        .returns(ExpectError(4, "Can't delegate to self"))
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .revoke_delegate(SECOND_USER)
+        .returns(ExpectError(4, "Delegate not authorized"))
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .authorize_delegate(SECOND_USER)
+        .returns(ReturnsResult)
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .authorize_delegate(SECOND_USER)
+        .returns(ExpectError(4, "Delegate already authorized"))
+        .run();
+
+    // the beneficiary is in another shard
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .authorize_delegate(OWNER_ADDRESS)
+        .returns(ReturnsResult)
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_for(SECOND_USER)
+        .returns(ExpectError(4, "wrong shard"))
+        .run();
+
+    world.write_scenario_trace("scenarios/delegate_invalid.scen.json");
+}
+
+#[test]
+fn on_chain_claim_allowed_contract_claim() {
+    let mut world = world();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .add_allowed_contract(OWNER_ADDRESS)
+        .returns(ExpectError(4, "Address is not a smart contract"))
+        .run();
+
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .add_allowed_contract(USER_SC_ADDRESS)
+        .returns(ExpectError(4, "Caller doesn't have the required role"))
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .add_allowed_contract(USER_SC_ADDRESS)
+        .returns(ReturnsResult)
+        .run();
+
+    world
+        .tx()
+        .from(USER_SC_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim()
+        .returns(ReturnsResult)
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .remove_allowed_contract(USER_SC_ADDRESS)
+        .returns(ReturnsResult)
+        .run();
+
+    world.current_block().block_epoch(2);
+    world
+        .tx()
+        .from(USER_SC_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim()
+        .returns(ExpectError(4, "Only user accounts can perform claim"))
+        .run();
+
+    world.write_scenario_trace("scenarios/allowed_contract_claim.scen.json");
+}
+
+fn signer() -> (PrivateKey, Address) {
+    let private_key = PrivateKey::from_hex_str(SIGNER_PRIVATE_KEY).unwrap();
+    let address = Address::from(PublicKey::from(&private_key).to_bytes());
+
+    (private_key, address)
+}
+
+/// Signs a claim the way the wallets sign messages.
+fn sign_claim(contract: &Address, epoch: u64, nonce: u64) -> ManagedByteArray<StaticApi, 64> {
+    let (private_key, signer_address) = signer();
+
+    let mut message = signer_address.to_vec();
+    message.extend_from_slice(&epoch.to_be_bytes());
+    message.extend_from_slice(contract.as_bytes());
+    message.extend_from_slice(&nonce.to_be_bytes());
+
+    let mut signed_data = format!("\x17Elrond Signed Message:\n{}", message.len()).into_bytes();
+    signed_data.extend_from_slice(&message);
+
+    ManagedByteArray::new_from_bytes(&private_key.sign(keccak256(&signed_data).to_vec()))
+}
+
+#[test]
+fn on_chain_claim_claim_with_signature() {
+    let mut world = world();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    let (_, signer_address) = signer();
+    let signature = sign_claim(&SC_ADDRESS.to_address(), 1, 0);
+
+    // relayed from another shard, for a signer that doesn't have an account yet
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_with_signature(&signer_address, 1u64, 0u64, &signature)
+        .returns(ReturnsResult)
+        .run();
+
+    let address_info = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_address_info(&signer_address)
+        .returns(ReturnsResult)
+        .run();
+    assert_eq!(address_info.current_streak, 1);
+    assert_eq!(address_info.last_epoch_claimed, 1);
+
+    let next_nonce = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .next_claim_nonce(&signer_address)
+        .returns(ReturnsResult)
+        .run();
+    assert_eq!(next_nonce, 1);
+
+    world.current_block().block_epoch(2);
+    let signature = sign_claim(&SC_ADDRESS.to_address(), 2, 1);
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_with_signature(&signer_address, 2u64, 1u64, &signature)
+        .returns(ReturnsResult)
+        .run();
+
+    let address_info = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_address_info(&signer_address)
+        .returns(ReturnsResult)
+        .run();
+    assert_eq!(address_info.current_streak, 2);
+
+    world.write_scenario_trace("scenarios/claim_with_signature.scen.json");
+}
+
+#[test]
+fn on_chain_claim_claim_with_signature_invalid() {
+    let mut world = world();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    let (_, signer_address) = signer();
+    world.current_block().block_epoch(2);
+
+    let signature = sign_claim(&SC_ADDRESS.to_address(), 1, 0);
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_with_signature(&signer_address, 1u64, 0u64, &signature)
+        .returns(ExpectError(4, "Signature is for another epoch"))
+        .run();
+
+    let signature = sign_claim(&SC_ADDRESS.to_address(), 2, 1);
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_with_signature(&signer_address, 2u64, 1u64, &signature)
+        .returns(ExpectError(4, "Invalid nonce"))
+        .run();
+
+    // signed for another contract
+    let signature = sign_claim(&USER_SC_ADDRESS.to_address(), 2, 0);
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_with_signature(&signer_address, 2u64, 0u64, &signature)
+        .returns(ExpectError(10, "invalid signature"))
+        .run();
+
+    // signed by someone else
+    let signature = sign_claim(&SC_ADDRESS.to_address(), 2, 0);
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_with_signature(OWNER_ADDRESS, 2u64, 0u64, &signature)
+        .returns(ExpectError(10, "invalid signature"))
+        .run();
+
+    let signature = sign_claim(&SC_ADDRESS.to_address(), 2, 0);
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_with_signature(&signer_address, 2u64, 0u64, &signature)
+        .returns(ReturnsResult)
+        .run();
+
+    // the same signature can't be replayed
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_with_signature(&signer_address, 2u64, 0u64, &signature)
+        .returns(ExpectError(4, "Invalid nonce"))
+        .run();
+
+    world.write_scenario_trace("scenarios/claim_with_signature_invalid.scen.json");
+}
+
+fn role_holders(world: &mut ScenarioWorld, role: proxy::Role) -> Vec<ManagedAddress<StaticApi>> {
+    world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .role_holders(role)
+        .returns(ReturnsResult)
+        .run()
+        .into_iter()
+        .collect()
+}
+
+/// Proposes a config change at `epoch`, then executes it once the timelock has passed.
+fn apply_config_change(
+    world: &mut ScenarioWorld,
+    from: Address,
+    change: proxy::ConfigChange<StaticApi>,
+    epoch: u64,
+) {
+    world.current_block().block_epoch(epoch);
+    let id = world
+        .tx()
+        .from(&from)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .propose_config_change(change)
+        .returns(ReturnsResult)
+        .run();
+
+    world.current_block().block_epoch(epoch + TIMELOCK_EPOCHS);
+    world
+        .tx()
+        .from(&from)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .execute_config_change(id)
+        .returns(ReturnsResult)
+        .run();
+}
+
+fn repair_streak_payment_change() -> proxy::ConfigChange<StaticApi> {
+    proxy::ConfigChange::SetRepairStreakPayment {
+        token_identifier: TOKEN.into(),
+        token_nonce: 0,
+    }
+}
+
+#[test]
+fn on_chain_claim_roles() {
+    let mut world = world();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    assert_eq!(
+        role_holders(&mut world, proxy::Role::PricingManager),
+        [OWNER_ADDRESS.to_managed_address()]
+    );
+
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .propose_config_change(repair_streak_payment_change())
+        .returns(ExpectError(4, "Caller doesn't have the required role"))
+        .run();
+
+    apply_config_change(
+        &mut world,
+        OWNER_ADDRESS.to_address(),
+        proxy::ConfigChange::GrantRole {
+            role: proxy::Role::PricingManager,
+            address: SECOND_USER.to_managed_address(),
+        },
+        1,
+    );
+
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .propose_config_change(repair_streak_payment_change())
+        .returns(ReturnsResult)
+        .run();
+
+    // each role only opens its own endpoints
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .open_season(ManagedBuffer::from("Season 1"), 5u64, 8u64)
+        .returns(ExpectError(4, "Caller doesn't have the required role"))
+        .run();
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .propose_config_change(proxy::ConfigChange::GrantRole {
+            role: proxy::Role::StateEditor,
+            address: SECOND_USER.to_managed_address(),
+        })
+        .returns(ExpectError(4, "Caller doesn't have the required role"))
+        .run();
+
+    // role managers can change roles too
+    apply_config_change(
+        &mut world,
+        OWNER_ADDRESS.to_address(),
+        proxy::ConfigChange::GrantRole {
+            role: proxy::Role::RoleManager,
+            address: USER_SC_ADDRESS.to_managed_address(),
+        },
+        4,
+    );
+    apply_config_change(
+        &mut world,
+        USER_SC_ADDRESS.to_address(),
+        proxy::ConfigChange::GrantRole {
+            role: proxy::Role::Pauser,
+            address: SECOND_USER.to_managed_address(),
+        },
+        7,
+    );
+
+    let has_role = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .has_role(proxy::Role::Pauser, SECOND_USER)
+        .returns(ReturnsResult)
+        .run();
+    assert!(has_role);
+
+    apply_config_change(
+        &mut world,
+        USER_SC_ADDRESS.to_address(),
+        proxy::ConfigChange::RevokeRole {
+            role: proxy::Role::PricingManager,
+            address: SECOND_USER.to_managed_address(),
+        },
+        10,
+    );
+
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .propose_config_change(repair_streak_payment_change())
+        .returns(ExpectError(4, "Caller doesn't have the required role"))
+        .run();
+
+    assert_eq!(
+        role_holders(&mut world, proxy::Role::PricingManager),
+        [OWNER_ADDRESS.to_managed_address()]
+    );
+    assert_eq!(
+        role_holders(&mut world, proxy::Role::Pauser),
+        [
+            OWNER_ADDRESS.to_managed_address(),
+            SECOND_USER.to_managed_address()
+        ]
+    );
+
+    world.write_scenario_trace("scenarios/roles.scen.json");
+}
+
+#[test]
+fn on_chain_claim_pause() {
+    let mut world = world();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .pause()
+        .returns(ExpectError(4, "Caller doesn't have the required role"))
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .pause()
+        .returns(ReturnsResult)
+        .run();
+
+    let is_paused = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .paused()
+        .returns(ReturnsResult)
+        .run();
+    assert!(is_paused);
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim()
+        .returns(ExpectError(4, "Claims are paused"))
+        .run();
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_and_repair()
+        .payment((TokenIdentifier::from(TOKEN), 0u64, BigUint::from(1u64)))
+        .returns(ExpectError(4, "Claims are paused"))
+        .run();
+
+    // admin endpoints are not paused
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .update_state(OWNER_ADDRESS, 1u64, 0u64, 1u64, 1u64)
+        .returns(ReturnsResult)
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .unpause()
+        .returns(ReturnsResult)
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim()
+        .returns(ReturnsResult)
+        .run();
+
+    world.write_scenario_trace("scenarios/pause.scen.json");
+}
+
+// A contract deployed before roles, with the owner and the second user in the admin list.
+#[test]
+fn on_chain_claim_upgrade_migrates_admins() {
+    let mut world = world();
+
+    let context = InterpreterContext::new()
+        .with_dir(world.current_dir().clone())
+        .with_allowed_missing_files();
+    let mut contract = Account::new()
+        .code(BytesValue::interpret_from(
+            CODE_PATH.eval_to_expr(),
+            &context,
+        ))
+        .owner(OWNER_ADDRESS.eval_to_expr().as_str());
+    let legacy_admin_storage = [
+        ("str:only_admin_module:admins.len", "u32:2".to_string()),
+        (
+            "str:only_admin_module:admins.item|u32:1",
+            OWNER_ADDRESS.eval_to_expr(),
+        ),
+        (
+            "str:only_admin_module:admins.item|u32:2",
+            SECOND_USER.eval_to_expr(),
+        ),
+        (
+            &format!(
+                "str:only_admin_module:admins.index|{}",
+                OWNER_ADDRESS.eval_to_expr()
+            ),
+            "u32:1".to_string(),
+        ),
+        (
+            &format!(
+                "str:only_admin_module:admins.index|{}",
+                SECOND_USER.eval_to_expr()
+            ),
+            "u32:2".to_string(),
+        ),
+    ];
+    for (key, value) in legacy_admin_storage {
+        contract
+            .storage
+            .insert(BytesKey::from(key), BytesValue::from(value.as_str()));
+    }
+    world.set_state_step(
+        SetStateStep::new().put_account(SC_ADDRESS.eval_to_expr().as_str(), contract),
+    );
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .upgrade()
+        .code(CODE_PATH)
+        .run();
+
+    for role in [
+        proxy::Role::StateEditor,
+        proxy::Role::PricingManager,
+        proxy::Role::Pauser,
+        proxy::Role::RoleManager,
+    ] {
+        assert_eq!(
+            role_holders(&mut world, role),
+            [
+                OWNER_ADDRESS.to_managed_address(),
+                SECOND_USER.to_managed_address()
+            ]
+        );
+    }
+
+    world.write_scenario_trace("scenarios/upgrade_migrates_admins.scen.json");
+}
+
+fn pending_config_changes(
+    world: &mut ScenarioWorld,
+) -> Vec<proxy::ConfigChangeProposal<StaticApi>> {
+    world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_pending_config_changes()
+        .returns(ReturnsResult)
+        .run()
+        .into_iter()
+        .collect()
+}
+
+#[test]
+fn on_chain_claim_config_change_timelock() {
+    let mut world = world();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .propose_config_change(proxy::ConfigChange::SetMaxRepairGap { max_repair_gap: 0 })
+        .returns(ExpectError(4, "Invalid repair gap"))
+        .run();
+    for max_repair_gap in [MAX_ALLOWED_REPAIR_GAP + 1, u64::MAX] {
+        world
+            .tx()
+            .from(OWNER_ADDRESS)
+            .to(SC_ADDRESS)
+            .typed(proxy::OnChainClaimContractProxy)
+            .propose_config_change(proxy::ConfigChange::SetMaxRepairGap { max_repair_gap })
+            .returns(ExpectError(4, "Invalid repair gap"))
+            .run();
+    }
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .propose_config_change(proxy::ConfigChange::SetRepairStreakPayment {
+            token_identifier: INVALID_TOKEN.into(),
+            token_nonce: 0,
+        })
+        .returns(ExpectError(4, "Invalid token ID"))
+        .run();
+
+    let (id, ()) = world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .propose_config_change(proxy::ConfigChange::SetMaxRepairGap { max_repair_gap: 10 })
+        .returns(ReturnsResult)
+        .returns(ExpectLogs(vec![contract_log(
+            "proposeConfigChange",
+            vec![
+                "str:config_change_proposed".to_string(),
+                "1".to_string(),
+                OWNER_ADDRESS.eval_to_expr(),
+                "1".to_string(),
+            ],
+            format!(
+                "u64:1|u8:1|u64:10|{}|u64:1|u64:{}",
+                OWNER_ADDRESS.eval_to_expr(),
+                1 + TIMELOCK_EPOCHS
+            ),
+        )]))
+        .run();
+    assert_eq!(id, 1);
+
+    let pending = pending_config_changes(&mut world);
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].proposer, OWNER_ADDRESS.to_managed_address());
+    assert_eq!(pending[0].executable_epoch, 1 + TIMELOCK_EPOCHS);
+
+    world.current_block().block_epoch(TIMELOCK_EPOCHS);
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .execute_config_change(1u64)
+        .returns(ExpectError(4, "Config change is still timelocked"))
+        .run();
+
+    world.current_block().block_epoch(1 + TIMELOCK_EPOCHS);
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .execute_config_change(1u64)
+        .returns(ExpectError(4, "Caller doesn't have the required role"))
+        .run();
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .execute_config_change(1u64)
+        .returns(ExpectLogs(vec![
+            admin_action_log(
+                "executeConfigChange",
+                5,
+                1 + TIMELOCK_EPOCHS,
+                "u8:11|u64:5|u64:10".to_string(),
+            ),
+            config_change_executed_log(1, 1 + TIMELOCK_EPOCHS),
+        ]))
+        .run();
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .execute_config_change(1u64)
+        .returns(ExpectError(4, "Unknown config change"))
+        .run();
+
+    let max_repair_gap = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_max_repair_gap()
+        .returns(ReturnsResult)
+        .run();
+    assert_eq!(max_repair_gap, 10);
+    assert!(pending_config_changes(&mut world).is_empty());
+
+    // 7 missed epochs can only be repaired with the new gap
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .update_state(OWNER_ADDRESS, 5u64, 1 + TIMELOCK_EPOCHS, 5u64, 5u64)
+        .returns(ReturnsResult)
+        .run();
+    world.current_block().block_epoch(TIMELOCK_EPOCHS + 9);
+    let can_be_repaired = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .can_be_repaired(OWNER_ADDRESS)
+        .returns(ReturnsResult)
+        .run();
+    assert!(can_be_repaired);
+
+    let proposed_epoch = TIMELOCK_EPOCHS + 9;
+    let (id, ()) = world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .propose_config_change(repair_streak_payment_change())
+        .returns(ReturnsResult)
+        .returns(ExpectLogs(vec![contract_log(
+            "proposeConfigChange",
+            vec![
+                "str:config_change_proposed".to_string(),
+                "2".to_string(),
+                OWNER_ADDRESS.eval_to_expr(),
+                proposed_epoch.to_string(),
+            ],
+            format!(
+                "u64:2|u8:0|nested:str:VLAD-6bde05|u64:0|{}|u64:{proposed_epoch}|u64:{}",
+                OWNER_ADDRESS.eval_to_expr(),
+                proposed_epoch + TIMELOCK_EPOCHS
+            ),
+        )]))
+        .run();
+
+    let executed_epoch = proposed_epoch + TIMELOCK_EPOCHS;
+    world.current_block().block_epoch(executed_epoch);
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .execute_config_change(id)
+        .returns(ExpectLogs(vec![
+            ExpectedLog {
+                address: SC_ADDRESS.eval_to_expr(),
+                endpoint: "executeConfigChange",
+                topics: vec![
+                    "str:new_set_repair_payment".to_string(),
+                    executed_epoch.to_string(),
+                    "str:VLAD-6bde05".to_string(),
+                    "0".to_string(),
+                    "1".to_string(),
+                ],
+                data: vec!["".to_string()],
+            },
+            admin_action_log(
+                "executeConfigChange",
+                7,
+                executed_epoch,
+                "u8:1|nested:str:VLAD-6bde05|u64:0|biguint:1|nested:str:VLAD-6bde05|u64:0|biguint:1"
+                    .to_string(),
+            ),
+            config_change_executed_log(id, executed_epoch),
+        ]))
+        .run();
+
+    world.write_scenario_trace("scenarios/config_change_timelock.scen.json");
+}
+
+#[test]
+fn on_chain_claim_config_change_cancel() {
+    let mut world = world();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    // the timelock itself is a config change, which can't remove it
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .propose_config_change(proxy::ConfigChange::SetTimelockEpochs { timelock_epochs: 0 })
+        .returns(ExpectError(4, "Invalid timelock"))
+        .run();
+    // nor make every later change wait forever
+    for timelock_epochs in [MAX_TIMELOCK_EPOCHS + 1, u64::MAX] {
+        world
+            .tx()
+            .from(OWNER_ADDRESS)
+            .to(SC_ADDRESS)
+            .typed(proxy::OnChainClaimContractProxy)
+            .propose_config_change(proxy::ConfigChange::SetTimelockEpochs { timelock_epochs })
+            .returns(ExpectError(4, "Invalid timelock"))
+            .run();
+    }
+    apply_config_change(
+        &mut world,
+        OWNER_ADDRESS.to_address(),
+        proxy::ConfigChange::SetTimelockEpochs { timelock_epochs: 1 },
+        1,
+    );
+    let timelock_epochs = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_timelock_epochs()
+        .returns(ReturnsResult)
+        .run();
+    assert_eq!(timelock_epochs, 1);
+
+    let id = world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .propose_config_change(repair_streak_payment_change())
+        .returns(ReturnsResult)
+        .run();
+
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .cancel_config_change(id)
+        .returns(ExpectError(4, "Caller doesn't have the required role"))
+        .run();
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .cancel_config_change(id)
+        .returns(ExpectLogs(vec![
+            ExpectedLog {
+                address: SC_ADDRESS.eval_to_expr(),
+                endpoint: "cancelConfigChange",
+                topics: vec![
+                    "str:config_change_cancelled".to_string(),
+                    id.to_string(),
+                    OWNER_ADDRESS.eval_to_expr(),
+                    (1 + TIMELOCK_EPOCHS).to_string(),
+                ],
+                data: vec!["".to_string()],
+            },
+            // the cancelled proposal, made with the timelock of 1 epoch
+            admin_action_log(
+                "cancelConfigChange",
+                6,
+                1 + TIMELOCK_EPOCHS,
+                format!(
+                    "u8:20|u64:{id}|u8:0|nested:str:VLAD-6bde05|u64:0|{}|u64:{}|u64:{}",
+                    OWNER_ADDRESS.eval_to_expr(),
+                    1 + TIMELOCK_EPOCHS,
+                    2 + TIMELOCK_EPOCHS
+                ),
+            ),
+        ]))
+        .run();
+
+    let change = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_config_change(id)
+        .returns(ReturnsResult)
+        .run();
+    assert!(change.is_none());
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .execute_config_change(id)
+        .returns(ExpectError(4, "Unknown config change"))
+        .run();
+
+    world.write_scenario_trace("scenarios/config_change_cancel.scen.json");
+}
+
+#[test]
+fn on_chain_claim_config_change_cancel_role_change() {
+    let mut world = world();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .new_address(SC_ADDRESS)
+        .run();
+    apply_config_change(
+        &mut world,
+        OWNER_ADDRESS.to_address(),
+        proxy::ConfigChange::GrantRole {
+            role: proxy::Role::RoleManager,
+            address: USER_SC_ADDRESS.to_managed_address(),
+        },
+        1,
+    );
+
+    // a role manager can't cancel the revocation of its role, nor any role change of another proposer
+    let revoke_id = world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .propose_config_change(proxy::ConfigChange::RevokeRole {
+            role: proxy::Role::RoleManager,
+            address: USER_SC_ADDRESS.to_managed_address(),
+        })
+        .returns(ReturnsResult)
+        .run();
+    world
+        .tx()
+        .from(USER_SC_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .cancel_config_change(revoke_id)
+        .returns(ExpectError(
+            4,
+            "Only the owner or the proposer can cancel a role change",
+        ))
+        .run();
+
+    // but it can cancel its own role changes
+    let grant_id = world
+        .tx()
+        .from(USER_SC_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .propose_config_change(proxy::ConfigChange::GrantRole {
+            role: proxy::Role::Pauser,
+            address: SECOND_USER.to_managed_address(),
+        })
+        .returns(ReturnsResult)
+        .run();
+    world
+        .tx()
+        .from(USER_SC_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .cancel_config_change(grant_id)
+        .run();
+
+    world.current_block().block_epoch(1 + 2 * TIMELOCK_EPOCHS);
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .execute_config_change(revoke_id)
+        .run();
+    assert_eq!(
+        role_holders(&mut world, proxy::Role::RoleManager),
+        [OWNER_ADDRESS.to_managed_address()]
+    );
+    assert!(pending_config_changes(&mut world).is_empty());
+
+    world.write_scenario_trace("scenarios/config_change_cancel_role_change.scen.json");
+}
+
+/// Makes the user contract a second state editor and requires both of them to approve state overrides,
+/// which expire after 5 epochs. Ends in the epoch after the timelock.
+fn setup_state_override_quorum(world: &mut ScenarioWorld) {
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    apply_config_change(
+        world,
+        OWNER_ADDRESS.to_address(),
+        proxy::ConfigChange::GrantRole {
+            role: proxy::Role::StateEditor,
+            address: USER_SC_ADDRESS.to_managed_address(),
+        },
+        1,
+    );
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .set_state_override_quorum(2u32, 5u64)
+        .returns(ExpectLogs(vec![admin_action_log(
+            "setStateOverrideQuorum",
+            6,
+            1 + TIMELOCK_EPOCHS,
+            "u8:13|u32:0|u32:2|u64:0|u64:5".to_string(),
+        )]))
+        .run();
+}
+
+fn pending_state_overrides(
+    world: &mut ScenarioWorld,
+) -> Vec<proxy::StateOverrideProposal<StaticApi>> {
+    world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_pending_state_overrides()
+        .returns(ReturnsResult)
+        .run()
+        .into_iter()
+        .collect()
+}
+
+#[test]
+fn on_chain_claim_state_override_quorum() {
+    let mut world = world();
+    setup_state_override_quorum(&mut world);
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .update_state(OWNER_ADDRESS, 1u64, 2u64, 20u64, 40u64)
+        .returns(ReturnsResult)
+        .run();
+
+    // only proposed, with the approval of the proposer
+    let address_info = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_address_info(OWNER_ADDRESS)
+        .returns(ReturnsResult)
+        .run();
+    assert_eq!(address_info.total_epochs_claimed, 0);
+
+    let pending = pending_state_overrides(&mut world);
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].address, OWNER_ADDRESS.to_managed_address());
+    assert_eq!(pending[0].expiry_epoch, 1 + TIMELOCK_EPOCHS + 5);
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .approve_state_override(1u64)
+        .returns(ExpectError(4, "State override already approved"))
+        .run();
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .approve_state_override(1u64)
+        .returns(ExpectError(4, "Caller doesn't have the required role"))
+        .run();
+
+    world
+        .tx()
+        .from(USER_SC_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .approve_state_override(1u64)
+        .returns(ExpectLogs(vec![
+            contract_log(
+                "approveStateOverride",
+                vec![
+                    "str:state_override_approved".to_string(),
+                    "1".to_string(),
+                    USER_SC_ADDRESS.eval_to_expr(),
+                    (1 + TIMELOCK_EPOCHS).to_string(),
+                ],
+                "".to_string(),
+            ),
+            admin_action_log_from(
+                "approveStateOverride",
+                7,
+                USER_SC_ADDRESS.eval_to_expr(),
+                1 + TIMELOCK_EPOCHS,
+                "u8:18|u64:1".to_string(),
+            ),
+            contract_log(
+                "approveStateOverride",
+                vec![
+                    "str:new_update_state".to_string(),
+                    OWNER_ADDRESS.eval_to_expr(),
+                    (1 + TIMELOCK_EPOCHS).to_string(),
+                ],
+                address_info_update_expr([0, 0, 0, 0], [1, 2, 20, 40]),
+            ),
+            admin_action_log_from(
+                "approveStateOverride",
+                8,
+                USER_SC_ADDRESS.eval_to_expr(),
+                1 + TIMELOCK_EPOCHS,
+                format!(
+                    "u8:0|{}|{}|{}",
+                    OWNER_ADDRESS.eval_to_expr(),
+                    address_info_expr([0, 0, 0, 0]),
+                    address_info_expr([1, 2, 20, 40])
+                ),
+            ),
+            contract_log(
+                "approveStateOverride",
+                vec![
+                    "str:state_override_executed".to_string(),
+                    "1".to_string(),
+                    (1 + TIMELOCK_EPOCHS).to_string(),
+                ],
+                "".to_string(),
+            ),
+        ]))
+        .run();
+
+    let address_info = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_address_info(OWNER_ADDRESS)
+        .returns(ReturnsResult)
+        .run();
+    assert_eq!(address_info.current_streak, 1);
+    assert_eq!(address_info.last_epoch_claimed, 2);
+    assert_eq!(address_info.total_epochs_claimed, 20);
+    assert_eq!(address_info.best_streak, 40);
+    assert!(pending_state_overrides(&mut world).is_empty());
+
+    world
+        .tx()
+        .from(USER_SC_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .approve_state_override(1u64)
+        .returns(ExpectError(4, "Unknown state override"))
+        .run();
+
+    world.write_scenario_trace("scenarios/state_override_quorum.scen.json");
+}
+
+#[test]
+fn on_chain_claim_state_override_expiry() {
+    let mut world = world();
+    setup_state_override_quorum(&mut world);
+
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .set_state_override_quorum(1u32, 0u64)
+        .returns(ExpectError(4, "Endpoint can only be called by owner"))
+        .run();
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .set_state_override_quorum(3u32, 5u64)
+        .returns(ExpectError(4, "Quorum exceeds the state editors"))
+        .run();
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .set_state_override_quorum(2u32, 0u64)
+        .returns(ExpectError(4, "Invalid expiry"))
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .update_state(OWNER_ADDRESS, 1u64, 2u64, 20u64, 40u64)
+        .returns(ReturnsResult)
+        .run();
+
+    world.current_block().block_epoch(1 + TIMELOCK_EPOCHS + 6);
+    world
+        .tx()
+        .from(USER_SC_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .approve_state_override(1u64)
+        .returns(ExpectError(4, "State override expired"))
+        .run();
+
+    world
+        .tx()
+        .from(USER_SC_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .cancel_state_override(1u64)
+        .returns(ExpectError(4, "Only the proposer or the owner can cancel"))
+        .run();
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .cancel_state_override(1u64)
+        .returns(ExpectLogs(vec![
+            contract_log(
+                "cancelStateOverride",
+                vec![
+                    "str:state_override_cancelled".to_string(),
+                    "1".to_string(),
+                    OWNER_ADDRESS.eval_to_expr(),
+                    (1 + TIMELOCK_EPOCHS + 6).to_string(),
+                ],
+                "".to_string(),
+            ),
+            admin_action_log(
+                "cancelStateOverride",
+                7,
+                1 + TIMELOCK_EPOCHS + 6,
+                format!(
+                    "u8:19|u64:1|{}|{}|{}|u64:{}|u64:{}",
+                    OWNER_ADDRESS.eval_to_expr(),
+                    address_info_expr([1, 2, 20, 40]),
+                    OWNER_ADDRESS.eval_to_expr(),
+                    1 + TIMELOCK_EPOCHS,
+                    1 + TIMELOCK_EPOCHS + 5
+                ),
+            ),
+        ]))
+        .run();
+    assert!(pending_state_overrides(&mut world).is_empty());
+
+    // back to direct overrides
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .set_state_override_quorum(1u32, 0u64)
+        .returns(ExpectLogs(vec![admin_action_log(
+            "setStateOverrideQuorum",
+            8,
+            1 + TIMELOCK_EPOCHS + 6,
+            "u8:13|u32:2|u32:1|u64:5|u64:0".to_string(),
+        )]))
+        .run();
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .update_state(OWNER_ADDRESS, 1u64, 2u64, 20u64, 40u64)
+        .returns(ReturnsResult)
+        .run();
+    let address_info = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_address_info(OWNER_ADDRESS)
+        .returns(ReturnsResult)
+        .run();
+    assert_eq!(address_info.total_epochs_claimed, 20);
+
+    world.write_scenario_trace("scenarios/state_override_expiry.scen.json");
+}
+
+fn claim_history(world: &mut ScenarioWorld) -> Vec<(u64, proxy::ClaimHistoryKind)> {
+    world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_claim_history(OWNER_ADDRESS)
+        .returns(ReturnsResult)
+        .run()
+        .into_iter()
+        .map(|entry| (entry.epoch, entry.kind))
+        .collect()
+}
+
+#[test]
+fn on_chain_claim_claim_history() {
+    let mut world = world();
+
+    setup_contract_with_burn_role(&mut world);
+
+    claim_at_epoch(&mut world, 1);
+    world.current_block().block_epoch(5);
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_and_repair()
+        .payment((TokenIdentifier::from(TOKEN), 0u64, BigUint::from(1u64)))
+        .returns(ReturnsResult)
+        .run();
+
+    assert_eq!(
+        claim_history(&mut world),
+        [
+            (1, proxy::ClaimHistoryKind::Claim),
+            (2, proxy::ClaimHistoryKind::Repair),
+            (3, proxy::ClaimHistoryKind::Repair),
+            (4, proxy::ClaimHistoryKind::Repair),
+            (5, proxy::ClaimHistoryKind::Claim),
+        ]
+    );
+
+    world.write_scenario_trace("scenarios/claim_history.scen.json");
+}
+
+/// Checks the stored entries of the owner's history by sequence number, an empty value being an evicted entry.
+fn check_claim_history_entries(world: &mut ScenarioWorld, entries: &[(u64, &str)]) {
+    let mut account = CheckAccount::new();
+    for (index, entry) in entries {
+        account = account.check_storage(
+            &format!(
+                "str:claim_history_entry|{}|u64:{index}",
+                OWNER_ADDRESS.eval_to_expr()
+            ),
+            entry,
+        );
+    }
+    if let CheckStorage::Equal(details) = &mut account.storage {
+        details.other_storages_allowed = true;
+    }
+
+    world.check_state_step(CheckStateStep::new().put_account(SC_ADDRESS, account));
+}
+
+#[test]
+fn on_chain_claim_claim_history_size() {
+    let mut world = world();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .set_claim_history_size(3u32)
+        .returns(ExpectError(4, "Caller doesn't have the required role"))
+        .run();
+    for size in [0u32, 101] {
+        world
+            .tx()
+            .from(OWNER_ADDRESS)
+            .to(SC_ADDRESS)
+            .typed(proxy::OnChainClaimContractProxy)
+            .set_claim_history_size(size)
+            .returns(ExpectError(4, "Invalid claim history size"))
+            .run();
+    }
+
+    for epoch in 1..=4 {
+        claim_at_epoch(&mut world, epoch);
+    }
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .update_state(OWNER_ADDRESS, 1u64, 4u64, 1u64, 4u64)
+        .returns(ReturnsResult)
+        .run();
+    assert_eq!(claim_history(&mut world).len(), 5);
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .set_claim_history_size(3u32)
+        .returns(ReturnsResult)
+        .run();
+    assert_eq!(
+        claim_history(&mut world),
+        [
+            (3, proxy::ClaimHistoryKind::Claim),
+            (4, proxy::ClaimHistoryKind::Claim),
+            (4, proxy::ClaimHistoryKind::AdminSet),
+        ]
+    );
+
+    // each new entry only evicts 2 of the entries out of the smaller history
+    claim_at_epoch(&mut world, 5);
+    assert_eq!(
+        claim_history(&mut world),
+        [
+            (4, proxy::ClaimHistoryKind::Claim),
+            (4, proxy::ClaimHistoryKind::AdminSet),
+            (5, proxy::ClaimHistoryKind::Claim),
+        ]
+    );
+    check_claim_history_entries(
+        &mut world,
+        &[(0, ""), (1, ""), (2, "u64:3|u8:0"), (3, "u64:4|u8:0")],
+    );
+
+    claim_at_epoch(&mut world, 6);
+    check_claim_history_entries(&mut world, &[(2, ""), (3, ""), (4, "u64:4|u8:2")]);
+
+    world.write_scenario_trace("scenarios/claim_history_size.scen.json");
+}
+
+#[test]
+fn on_chain_claim_claim_bitmap() {
+    let mut world = world();
+
+    setup_contract_with_burn_role(&mut world);
+
+    claim_at_epoch(&mut world, 1);
+    claim_at_epoch(&mut world, 2);
+    world.current_block().block_epoch(5);
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_and_repair()
+        .payment((TokenIdentifier::from(TOKEN), 0u64, BigUint::from(1u64)))
+        .returns(ReturnsResult)
+        .run();
+    claim_at_epoch(&mut world, 64);
+
+    let words = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_claim_bitmap(OWNER_ADDRESS, 2u64, 70u64)
+        .returns(ReturnsResult)
+        .run()
+        .into_iter()
+        .map(|word| (word.first_epoch, word.claimed, word.repaired))
+        .collect::<Vec<_>>();
+    // epoch 1 is out of the range
+    assert_eq!(words, [(0, 1 << 2 | 1 << 5, 1 << 3 | 1 << 4), (64, 1, 0)]);
+
+    world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_claim_bitmap(OWNER_ADDRESS, 5u64, 4u64)
+        .returns(ExpectError(4, "Invalid epoch range"))
+        .run();
+    world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_claim_bitmap(OWNER_ADDRESS, 0u64, 64u64 * 64)
+        .returns(ExpectError(4, "Epoch range too large"))
+        .run();
+
+    world.write_scenario_trace("scenarios/claim_bitmap.scen.json");
+}
+
+fn setup_badges(world: &mut ScenarioWorld) {
+    // The issue callback only runs when the system smart contract has an account, which needs some code
+    world
+        .account(ESDTSystemSCAddress.to_managed_address())
+        .code(CODE_PATH);
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .issue_badge_collection("Streak badges", "STREAK")
+        .egld(50)
+        .run();
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .set_badge_tiers(2u64, 3u64, 5u64)
+        .run();
+}
+
+fn badge(world: &mut ScenarioWorld) -> Option<(proxy::BadgeTier, u64, u64)> {
+    world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_badge(OWNER_ADDRESS)
+        .returns(ReturnsResult)
+        .run()
+        .into_option()
+        .map(|badge| {
+            assert_eq!(badge.owner, OWNER_ADDRESS);
+            (badge.tier, badge.best_streak, badge.tier_epoch)
+        })
+}
+
+fn badge_nonce(world: &mut ScenarioWorld) -> u64 {
+    world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .badge_nonce(OWNER_ADDRESS)
+        .returns(ReturnsResult)
+        .run()
+}
+
+#[test]
+fn on_chain_claim_badges() {
+    let mut world = world();
+
+    setup_badges(&mut world);
+
+    claim_at_epoch(&mut world, 1);
+    assert_eq!(badge(&mut world), None);
+    assert_eq!(badge_nonce(&mut world), 0);
+
+    claim_at_epoch(&mut world, 2);
+    assert_eq!(badge(&mut world), Some((proxy::BadgeTier::Bronze, 2, 2)));
+    let nonce = badge_nonce(&mut world);
+    assert_eq!(nonce, 1);
+
+    claim_at_epoch(&mut world, 3);
+    assert_eq!(badge(&mut world), Some((proxy::BadgeTier::Silver, 3, 3)));
+
+    // Same tier, the badge keeps the streak it was awarded with
+    claim_at_epoch(&mut world, 4);
+    assert_eq!(badge(&mut world), Some((proxy::BadgeTier::Silver, 3, 3)));
+
+    claim_at_epoch(&mut world, 5);
+    assert_eq!(badge(&mut world), Some((proxy::BadgeTier::Gold, 5, 5)));
+    assert_eq!(badge_nonce(&mut world), nonce);
+
+    // Never downgraded by a state override
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .update_state(OWNER_ADDRESS, 1u64, 5u64, 5u64, 1u64)
+        .run();
+    assert_eq!(badge(&mut world), Some((proxy::BadgeTier::Gold, 5, 5)));
+
+    let token_id = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .badge_token()
+        .returns(ReturnsResult)
+        .run();
+    world
+        .check_account(SC_ADDRESS)
+        .esdt_nft_balance_and_attributes(
+            &token_id,
+            nonce,
+            1,
+            proxy::BadgeAttributes::<StaticApi> {
+                owner: OWNER_ADDRESS.to_managed_address(),
+                tier: proxy::BadgeTier::Gold,
+                best_streak: 5,
+                tier_epoch: 5,
+            },
+        );
+
+    world.write_scenario_trace("scenarios/badges.scen.json");
+}
+
+#[test]
+fn on_chain_claim_badges_invalid() {
+    let mut world = world();
+
+    setup_badges(&mut world);
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .issue_badge_collection("Streak badges", "STREAK")
+        .egld(50)
+        .returns(ExpectError(4, "Badge collection already issued"))
+        .run();
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .set_badge_tiers(3u64, 3u64, 5u64)
+        .returns(ExpectError(4, "Invalid badge tiers"))
+        .run();
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .set_badge_tiers(0u64, 3u64, 5u64)
+        .returns(ExpectError(4, "Invalid badge tiers"))
+        .run();
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .set_badge_tiers(1u64, 3u64, 5u64)
+        .returns(ExpectError(4, "Caller doesn't have the required role"))
+        .run();
+
+    world.write_scenario_trace("scenarios/badges_invalid.scen.json");
+}
+
+fn has_min_streak(world: &mut ScenarioWorld, address: TestAddress, min_streak: u64) -> bool {
+    world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(access_proxy::OnChainClaimContractProxy)
+        .has_min_streak(address, min_streak)
+        .returns(ReturnsResult)
+        .run()
+}
+
+fn has_claimed_in_last_epochs(world: &mut ScenarioWorld, epochs: u64) -> bool {
+    world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(access_proxy::OnChainClaimContractProxy)
+        .has_claimed_in_last_epochs(OWNER_ADDRESS, epochs)
+        .returns(ReturnsResult)
+        .run()
+}
+
+fn is_active_claimer(world: &mut ScenarioWorld) -> bool {
+    world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(access_proxy::OnChainClaimContractProxy)
+        .is_active_claimer(OWNER_ADDRESS)
+        .returns(ReturnsResult)
+        .run()
+}
+
+#[test]
+fn on_chain_claim_access_views() {
+    let mut world = world();
+
+    setup_contract_with_burn_role(&mut world);
+
+    claim_at_epoch(&mut world, 1);
+    claim_at_epoch(&mut world, 2);
+    claim_at_epoch(&mut world, 3);
+    assert!(has_min_streak(&mut world, OWNER_ADDRESS, 3));
+    assert!(!has_min_streak(&mut world, OWNER_ADDRESS, 4));
+    assert!(has_claimed_in_last_epochs(&mut world, 1));
+    assert!(is_active_claimer(&mut world));
+
+    // The streak can still be extended in the next epoch
+    world.current_block().block_epoch(4);
+    assert!(has_min_streak(&mut world, OWNER_ADDRESS, 3));
+    assert!(!has_claimed_in_last_epochs(&mut world, 1));
+    assert!(is_active_claimer(&mut world));
+
+    world.current_block().block_epoch(5);
+    assert!(!has_min_streak(&mut world, OWNER_ADDRESS, 1));
+    assert!(has_claimed_in_last_epochs(&mut world, 3));
+    assert!(!has_claimed_in_last_epochs(&mut world, 2));
+    assert!(!is_active_claimer(&mut world));
+
+    // Addresses that never claimed pass no check
+    assert!(!has_min_streak(&mut world, SECOND_USER, 0));
+
+    world.write_scenario_trace("scenarios/access_views.scen.json");
+}
+
+fn set_referral_milestones(world: &mut ScenarioWorld, milestones: &[(u64, u64)]) {
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .set_referral_milestones(MultiValueVec::from(
+            milestones
+                .iter()
+                .map(|&milestone| MultiValue2::from(milestone))
+                .collect::<Vec<_>>(),
+        ))
+        .run();
+}
+
+fn claim_from_user_sc_at_epoch(world: &mut ScenarioWorld, epoch: u64) {
+    world.current_block().block_epoch(epoch);
+    world
+        .tx()
+        .from(USER_SC_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim()
+        .run();
+}
+
+fn referral_points(world: &mut ScenarioWorld) -> u64 {
+    world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .referral_points(OWNER_ADDRESS)
+        .returns(ReturnsResult)
+        .run()
+}
+
+#[test]
+fn on_chain_claim_referrals() {
+    let mut world = world();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .new_address(SC_ADDRESS)
+        .run();
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .add_allowed_contract(USER_SC_ADDRESS)
+        .run();
+    set_referral_milestones(&mut world, &[(2, 10), (3, 25)]);
+
+    claim_at_epoch(&mut world, 1);
+    world
+        .tx()
+        .from(USER_SC_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_with_referrer(OWNER_ADDRESS)
+        .run();
+
+    let referrer = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_referrer(USER_SC_ADDRESS)
+        .returns(ReturnsResult)
+        .run()
+        .into_option();
+    assert_eq!(referrer, Some(OWNER_ADDRESS.to_managed_address()));
+    let referees = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_referees(OWNER_ADDRESS, 0usize, 10usize)
+        .returns(ReturnsResult)
+        .run()
+        .into_iter()
+        .collect::<Vec<_>>();
+    assert_eq!(referees, [USER_SC_ADDRESS.to_managed_address()]);
+    let referees = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_referees(OWNER_ADDRESS, 1usize, 10usize)
+        .returns(ReturnsResult)
+        .run();
+    assert!(referees.is_empty());
+    assert_eq!(referral_points(&mut world), 0);
+
+    claim_from_user_sc_at_epoch(&mut world, 2);
+    assert_eq!(referral_points(&mut world), 10);
+    claim_from_user_sc_at_epoch(&mut world, 3);
+    assert_eq!(referral_points(&mut world), 35);
+
+    // The streak restarts, the milestone of 3 epochs was already rewarded
+    set_referral_milestones(&mut world, &[(3, 50), (4, 100)]);
+    claim_from_user_sc_at_epoch(&mut world, 5);
+    claim_from_user_sc_at_epoch(&mut world, 6);
+    claim_from_user_sc_at_epoch(&mut world, 7);
+    assert_eq!(referral_points(&mut world), 35);
+    claim_from_user_sc_at_epoch(&mut world, 8);
+    assert_eq!(referral_points(&mut world), 135);
+
+    // A streak passing no milestone doesn't skip the milestones added later
+    set_referral_milestones(&mut world, &[(3, 50), (4, 100), (6, 200)]);
+    claim_from_user_sc_at_epoch(&mut world, 9);
+    assert_eq!(referral_points(&mut world), 135);
+    set_referral_milestones(&mut world, &[(3, 50), (4, 100), (5, 5), (6, 200)]);
+    claim_from_user_sc_at_epoch(&mut world, 10);
+    assert_eq!(referral_points(&mut world), 340);
+
+    world.write_scenario_trace("scenarios/referrals.scen.json");
+}
+
+#[test]
+fn on_chain_claim_referral_invalid() {
+    let mut world = world();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .new_address(SC_ADDRESS)
+        .run();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_with_referrer(OWNER_ADDRESS)
+        .returns(ExpectError(4, "Can't refer yourself"))
+        .run();
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_with_referrer(SECOND_USER)
+        .returns(ExpectError(4, "Referrer never claimed"))
+        .run();
+
+    claim_at_epoch(&mut world, 1);
+    world.current_block().block_epoch(2);
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_with_referrer(USER_SC_ADDRESS)
+        .returns(ExpectError(4, "Only the first claim can have a referrer"))
+        .run();
+
+    for milestones in [vec![(3u64, 10u64), (2, 10)], vec![(2, 0)], vec![(0, 10)]] {
+        world
+            .tx()
+            .from(OWNER_ADDRESS)
+            .to(SC_ADDRESS)
+            .typed(proxy::OnChainClaimContractProxy)
+            .set_referral_milestones(MultiValueVec::from(
+                milestones
+                    .into_iter()
+                    .map(MultiValue2::from)
+                    .collect::<Vec<_>>(),
+            ))
+            .returns(ExpectError(4, "Invalid referral milestones"))
+            .run();
+    }
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .set_referral_milestones(MultiValueVec::from(
+            (1..=11u64)
+                .map(|streak| MultiValue2::from((streak, 1u64)))
+                .collect::<Vec<_>>(),
+        ))
+        .returns(ExpectError(4, "Too many referral milestones"))
+        .run();
+    world
+        .tx()
+        .from(SECOND_USER)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .set_referral_milestones(MultiValueVec::<MultiValue2<u64, u64>>::new())
+        .returns(ExpectError(4, "Caller doesn't have the required role"))
+        .run();
+
+    world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_referees(OWNER_ADDRESS, 0usize, 101usize)
+        .returns(ExpectError(4, "Page size too large"))
+        .run();
+
+    world.write_scenario_trace("scenarios/referral_invalid.scen.json");
+}
+
+fn claim_and_repair_at_epoch(world: &mut ScenarioWorld, epoch: u64) {
+    world.current_block().block_epoch(epoch);
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_and_repair()
+        .payment((TokenIdentifier::from(TOKEN), 0u64, BigUint::from(1u64)))
+        .run();
+}
+
+#[test]
+fn on_chain_claim_repair_payment_sink() {
+    let mut world = world();
+
+    // Deployed without the burn role of the repair token
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .new_address(SC_ADDRESS)
+        .run();
+    claim_at_epoch(&mut world, 1);
+
+    apply_config_change(
+        &mut world,
+        OWNER_ADDRESS.to_address(),
+        proxy::ConfigChange::SetRepairPaymentSink {
+            sink: proxy::RepairPaymentSink::Treasury {
+                treasury: SECOND_USER.to_managed_address(),
+            },
+        },
+        1,
+    );
+    claim_and_repair_at_epoch(&mut world, 1 + TIMELOCK_EPOCHS);
+    world
+        .check_account(SECOND_USER)
+        .esdt_balance(TOKEN, BigUint::from(1001u64));
+
+    // Half of a single token is burnt, rounded down, so the treasury gets all of it
+    let sink = proxy::RepairPaymentSink::Split {
+        treasury: SECOND_USER.to_managed_address(),
+        burn_percentage: 50,
+    };
+    apply_config_change(
+        &mut world,
+        OWNER_ADDRESS.to_address(),
+        proxy::ConfigChange::SetRepairPaymentSink { sink: sink.clone() },
+        1 + TIMELOCK_EPOCHS,
+    );
+    claim_and_repair_at_epoch(&mut world, 1 + 2 * TIMELOCK_EPOCHS);
+    world
+        .check_account(SECOND_USER)
+        .esdt_balance(TOKEN, BigUint::from(1002u64));
+    world.check_account(SC_ADDRESS).esdt_balance(TOKEN, 0u64);
+
+    let current_sink = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_repair_payment_sink()
+        .returns(ReturnsResult)
+        .run();
+    assert_eq!(current_sink, sink);
+
+    for sink in [
+        proxy::RepairPaymentSink::Treasury {
+            treasury: ManagedAddress::zero(),
+        },
+        proxy::RepairPaymentSink::Split {
+            treasury: SECOND_USER.to_managed_address(),
+            burn_percentage: 101,
+        },
+    ] {
+        world
+            .tx()
+            .from(OWNER_ADDRESS)
+            .to(SC_ADDRESS)
+            .typed(proxy::OnChainClaimContractProxy)
+            .propose_config_change(proxy::ConfigChange::SetRepairPaymentSink { sink })
+            .returns(ExpectError(4, "Invalid repair payment sink"))
+            .run();
+    }
+
+    world.write_scenario_trace("scenarios/repair_payment_sink.scen.json");
+}
+
+fn egld_repair_payment(amount: u64) -> proxy::ConfigChange<StaticApi> {
+    proxy::ConfigChange::SetRepairPayment {
+        payment: EgldOrEsdtTokenPayment::new(
+            EgldOrEsdtTokenIdentifier::egld(),
+            0,
+            BigUint::from(amount),
+        ),
+    }
+}
+
+#[test]
+fn on_chain_claim_egld_repair_payment() {
+    let mut world = world();
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .code_metadata(CodeMetadata::PAYABLE)
+        .new_address(SC_ADDRESS)
+        .run();
+    claim_at_epoch(&mut world, 1);
+
+    // EGLD can't be burnt
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .propose_config_change(egld_repair_payment(10))
+        .returns(ExpectError(4, "EGLD repair payments need a treasury"))
+        .run();
+
+    apply_config_change(
+        &mut world,
+        OWNER_ADDRESS.to_address(),
+        proxy::ConfigChange::SetRepairPaymentSink {
+            sink: proxy::RepairPaymentSink::Split {
+                treasury: SECOND_USER.to_managed_address(),
+                burn_percentage: 50,
+            },
+        },
+        1,
+    );
+    apply_config_change(
+        &mut world,
+        OWNER_ADDRESS.to_address(),
+        egld_repair_payment(10),
+        1 + TIMELOCK_EPOCHS,
+    );
+
+    let payment = world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .repair_streak_payment()
+        .returns(ReturnsResult)
+        .run();
+    assert!(payment.token_identifier.is_egld());
+    assert_eq!(payment.amount, BigUint::from(10u64));
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_and_repair()
+        .payment((TokenIdentifier::from(TOKEN), 0u64, BigUint::from(1u64)))
+        .returns(ExpectError(4, "Bad payment token/amount"))
+        .run();
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_and_repair()
+        .egld(5)
+        .returns(ExpectError(4, "Bad payment token/amount"))
+        .run();
+
+    // The whole payment goes to the treasury, even with a split sink
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .claim_and_repair()
+        .egld(10)
+        .run();
+    world.check_account(OWNER_ADDRESS).balance(90);
+    world.check_account(SECOND_USER).balance(1010);
+    world.check_account(SC_ADDRESS).balance(0);
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .propose_config_change(proxy::ConfigChange::SetRepairPaymentSink {
+            sink: proxy::RepairPaymentSink::Burn,
+        })
+        .returns(ExpectError(4, "EGLD repair payments need a treasury"))
+        .run();
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .propose_config_change(egld_repair_payment(0))
+        .returns(ExpectError(4, "Invalid repair payment amount"))
+        .run();
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .propose_config_change(proxy::ConfigChange::SetRepairPayment {
+            payment: EgldOrEsdtTokenPayment::new(
+                EgldOrEsdtTokenIdentifier::egld(),
+                1,
+                BigUint::from(10u64),
+            ),
+        })
+        .returns(ExpectError(4, "Invalid token nonce"))
+        .run();
+
+    world.write_scenario_trace("scenarios/egld_repair_payment.scen.json");
 }
@@ -0,0 +1,23 @@
+use multiversx_sc_scenario::*;
+
+mod common;
+
+use common::scenario_paths;
+
+fn world() -> ScenarioWorld {
+    let mut blockchain = ScenarioWorld::new();
+
+    blockchain.register_contract(
+        "mxsc:output/on-chain-claim.mxsc.json",
+        on_chain_claim::ContractBuilder,
+    );
+    blockchain
+}
+
+#[test]
+fn all_scenarios_rs() {
+    for path in scenario_paths() {
+        println!("running {path}");
+        world().run(path);
+    }
+}
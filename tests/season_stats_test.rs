@@ -0,0 +1,60 @@
+use on_chain_claim::season::SeasonStats;
+
+const SEASON_ID: u64 = 2;
+const SEASON_START_EPOCH: u64 = 10;
+
+#[test]
+fn first_claim_in_season_test() {
+    let mut stats = SeasonStats::new(SEASON_ID);
+
+    stats.record_claim(SEASON_START_EPOCH, 12, 1, 1);
+    assert_eq!(
+        stats,
+        SeasonStats {
+            season_id: SEASON_ID,
+            current_streak: 1,
+            last_epoch_claimed: 12,
+            epochs_claimed: 1,
+            best_streak: 1,
+        }
+    );
+}
+
+#[test]
+fn streak_from_before_season_is_not_counted_test() {
+    let mut stats = SeasonStats::new(SEASON_ID);
+
+    stats.record_claim(SEASON_START_EPOCH, SEASON_START_EPOCH, 1, 7);
+    assert_eq!(stats.current_streak, 1);
+    assert_eq!(stats.best_streak, 1);
+
+    stats.record_claim(SEASON_START_EPOCH, SEASON_START_EPOCH + 1, 1, 8);
+    assert_eq!(stats.current_streak, 2);
+    assert_eq!(stats.epochs_claimed, 2);
+    assert_eq!(stats.best_streak, 2);
+}
+
+#[test]
+fn repair_across_season_start_test() {
+    let mut stats = SeasonStats::new(SEASON_ID);
+
+    // repairs epochs 8 to 11, only the ones from 10 on are in the season
+    stats.record_claim(SEASON_START_EPOCH, 11, 4, 6);
+    assert_eq!(stats.current_streak, 2);
+    assert_eq!(stats.epochs_claimed, 2);
+    assert_eq!(stats.best_streak, 2);
+}
+
+#[test]
+fn best_streak_survives_reset_test() {
+    let mut stats = SeasonStats::new(SEASON_ID);
+
+    for epoch in SEASON_START_EPOCH..SEASON_START_EPOCH + 3 {
+        stats.record_claim(SEASON_START_EPOCH, epoch, 1, epoch - SEASON_START_EPOCH + 1);
+    }
+    stats.record_claim(SEASON_START_EPOCH, 20, 1, 1);
+
+    assert_eq!(stats.current_streak, 1);
+    assert_eq!(stats.epochs_claimed, 4);
+    assert_eq!(stats.best_streak, 3);
+}
@@ -0,0 +1,228 @@
+use multiversx_sc_scenario::imports::*;
+use on_chain_claim::config::MAX_REPAIR_GAP;
+use proptest::prelude::*;
+
+mod proxy;
+
+const OWNER_ADDRESS: TestAddress = TestAddress::new("owner");
+const SC_ADDRESS: TestSCAddress = TestSCAddress::new("on-chain-claim");
+const CODE_PATH: MxscPath = MxscPath::new("output/on-chain-claim.mxsc.json");
+const TOKEN_ID: &str = "REPAIR-123456";
+const TOKEN: TestTokenIdentifier = TestTokenIdentifier::new(TOKEN_ID);
+const START_EPOCH: u64 = 1;
+// Keeps the values written by updateState far from overflowing in the streak arithmetic
+const MAX_STATE_VALUE: u64 = 1_000_000;
+
+#[derive(Debug, Clone)]
+enum Op {
+    Claim,
+    ClaimAndRepair,
+    UpdateState(proxy::AddressInfo),
+    AdvanceEpochs(u64),
+}
+
+fn op_strategy() -> impl Strategy<Value = Op> {
+    let address_info = (
+        0..MAX_STATE_VALUE,
+        0..MAX_STATE_VALUE,
+        0..MAX_STATE_VALUE,
+        0..MAX_STATE_VALUE,
+    )
+        .prop_map(
+            |(current_streak, last_epoch_claimed, total_epochs_claimed, best_streak)| {
+                proxy::AddressInfo {
+                    current_streak,
+                    last_epoch_claimed,
+                    total_epochs_claimed,
+                    best_streak,
+                }
+            },
+        );
+    // States close to the current epoch, to reach the interesting claim and repair paths after an update
+    let recent_address_info = (0..10u64, 0..40u64, 0..20u64, 0..10u64).prop_map(
+        |(current_streak, last_epoch_claimed, total_epochs_claimed, best_streak)| {
+            proxy::AddressInfo {
+                current_streak,
+                last_epoch_claimed,
+                total_epochs_claimed,
+                best_streak,
+            }
+        },
+    );
+
+    prop_oneof![
+        4 => Just(Op::Claim),
+        3 => Just(Op::ClaimAndRepair),
+        1 => address_info.prop_map(Op::UpdateState),
+        1 => recent_address_info.prop_map(Op::UpdateState),
+        4 => (0..2 * MAX_REPAIR_GAP + 2).prop_map(Op::AdvanceEpochs),
+    ]
+}
+
+/// Reference model of the streak state machine, for a single address.
+struct Model {
+    epoch: u64,
+    address_info: Option<proxy::AddressInfo>,
+}
+
+impl Model {
+    fn claim(&mut self) -> Result<(), &'static str> {
+        let Some(address_info) = &mut self.address_info else {
+            self.address_info = Some(proxy::AddressInfo {
+                current_streak: 1,
+                last_epoch_claimed: self.epoch,
+                total_epochs_claimed: 1,
+                best_streak: 1,
+            });
+            return Ok(());
+        };
+
+        if address_info.last_epoch_claimed >= self.epoch {
+            return Err("epoch already claimed");
+        }
+
+        address_info.current_streak = if address_info.last_epoch_claimed + 1 == self.epoch {
+            address_info.current_streak + 1
+        } else {
+            1
+        };
+        address_info.total_epochs_claimed += 1;
+        address_info.last_epoch_claimed = self.epoch;
+        address_info.best_streak = address_info.best_streak.max(address_info.current_streak);
+
+        Ok(())
+    }
+
+    fn claim_and_repair(&mut self) -> Result<(), &'static str> {
+        let Some(address_info) = &mut self.address_info else {
+            return Err("can't repair streak for address");
+        };
+
+        let missed_epochs = self
+            .epoch
+            .saturating_sub(address_info.last_epoch_claimed)
+            .saturating_sub(1);
+        if missed_epochs == 0 || missed_epochs > MAX_REPAIR_GAP + 1 {
+            return Err("can't repair streak for current epoch");
+        }
+
+        address_info.current_streak += missed_epochs + 1;
+        address_info.total_epochs_claimed += missed_epochs + 1;
+        address_info.last_epoch_claimed = self.epoch;
+        address_info.best_streak = address_info.best_streak.max(address_info.current_streak);
+
+        Ok(())
+    }
+
+    fn apply(&mut self, op: &Op) -> Result<(), &'static str> {
+        match op {
+            Op::Claim => self.claim(),
+            Op::ClaimAndRepair => self.claim_and_repair(),
+            Op::UpdateState(address_info) => {
+                self.address_info = Some(address_info.clone());
+                Ok(())
+            }
+            Op::AdvanceEpochs(num_epochs) => {
+                self.epoch += num_epochs;
+                Ok(())
+            }
+        }
+    }
+}
+
+fn setup() -> ScenarioWorld {
+    let mut world = ScenarioWorld::new();
+    world.register_contract(CODE_PATH, on_chain_claim::ContractBuilder);
+    world
+        .account(OWNER_ADDRESS)
+        .nonce(1)
+        .esdt_balance(TOKEN, 1_000);
+    world.current_block().block_epoch(START_EPOCH);
+
+    world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .init(TOKEN, 0u64)
+        .code(CODE_PATH)
+        .new_address(SC_ADDRESS)
+        .run();
+    world.set_esdt_local_roles(SC_ADDRESS, TOKEN_ID.as_bytes(), &[EsdtLocalRole::Burn]);
+
+    world
+}
+
+/// Runs an operation against the contract, returning the error message if the transaction failed.
+fn execute(world: &mut ScenarioWorld, epoch: u64, op: &Op) -> Result<(), String> {
+    let tx = world
+        .tx()
+        .from(OWNER_ADDRESS)
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy);
+    let (status, message) = match op {
+        Op::Claim => tx
+            .claim()
+            .returns(ReturnsStatus)
+            .returns(ReturnsMessage)
+            .run(),
+        Op::ClaimAndRepair => tx
+            .claim_and_repair()
+            .payment((TokenIdentifier::from(TOKEN), 0, BigUint::from(1u64)))
+            .returns(ReturnsStatus)
+            .returns(ReturnsMessage)
+            .run(),
+        Op::UpdateState(address_info) => tx
+            .update_state(
+                OWNER_ADDRESS,
+                address_info.current_streak,
+                address_info.last_epoch_claimed,
+                address_info.total_epochs_claimed,
+                address_info.best_streak,
+            )
+            .returns(ReturnsStatus)
+            .returns(ReturnsMessage)
+            .run(),
+        Op::AdvanceEpochs(_) => {
+            world.current_block().block_epoch(epoch);
+            return Ok(());
+        }
+    };
+
+    if status == 0 {
+        Ok(())
+    } else {
+        Err(message)
+    }
+}
+
+fn address_info(world: &mut ScenarioWorld) -> proxy::AddressInfo {
+    world
+        .query()
+        .to(SC_ADDRESS)
+        .typed(proxy::OnChainClaimContractProxy)
+        .get_address_info(OWNER_ADDRESS)
+        .returns(ReturnsResult)
+        .run()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn streak_state_machine_matches_model(ops in prop::collection::vec(op_strategy(), 1..40)) {
+        let mut world = setup();
+        let mut model = Model {
+            epoch: START_EPOCH,
+            address_info: None,
+        };
+
+        for op in &ops {
+            let expected = model.apply(op).map_err(str::to_string);
+            let actual = execute(&mut world, model.epoch, op);
+            prop_assert_eq!(actual, expected, "outcome of {:?}", op);
+
+            let expected_address_info = model.address_info.clone().unwrap_or_default();
+            prop_assert_eq!(address_info(&mut world), expected_address_info, "state after {:?}", op);
+        }
+    }
+}
@@ -6,9 +6,9 @@
 
 // Init:                                 1
 // Upgrade:                              1
-// Endpoints:                           11
-// Async Callback (empty):               1
-// Total number of exported functions:  14
+// Endpoints:                           64
+// Async Callback:                       1
+// Total number of exported functions:  67
 
 #![no_std]
 
@@ -21,17 +21,70 @@ multiversx_sc_wasm_adapter::endpoints! {
         init => init
         upgrade => upgrade
         claim => claim
+        claimWithReferrer => claim_with_referrer
+        claimFor => claim_for
+        claimWithSignature => claim_with_signature
         claimAndRepair => claim_and_repair
         updateState => update_state
-        setRepairStreakPayment => set_repair_streak_payment
         getAddressInfo => get_address_info
         canBeRepaired => can_be_repaired
+        getMaxRepairGap => get_max_repair_gap
+        getRepairPaymentSink => get_repair_payment_sink
         getRepairStreakPayment => repair_streak_payment
-        isAdmin => is_admin
-        addAdmin => add_admin
-        removeAdmin => remove_admin
-        getAdmins => admins
+        hasMinStreak => has_min_streak
+        hasClaimedInLastEpochs => has_claimed_in_last_epochs
+        isActiveClaimer => is_active_claimer
+        pause => pause
+        unpause => unpause
+        hasRole => has_role
+        getRoleHolders => role_holders
+        isPaused => paused
+        getAdminActionCount => admin_action_count
+        openSeason => open_season
+        closeSeason => close_season
+        getSeasons => get_seasons
+        getCurrentSeason => get_current_season
+        getSeasonStats => get_season_stats
+        authorizeDelegate => authorize_delegate
+        revokeDelegate => revoke_delegate
+        addAllowedContract => add_allowed_contract
+        removeAllowedContract => remove_allowed_contract
+        isDelegate => is_delegate
+        isAllowedContract => is_allowed_contract
+        getDelegates => delegates
+        getBeneficiaries => beneficiaries
+        getAllowedContracts => allowed_contracts
+        getNextClaimNonce => next_claim_nonce
+        setStateOverrideQuorum => set_state_override_quorum
+        approveStateOverride => approve_state_override
+        cancelStateOverride => cancel_state_override
+        getPendingStateOverrides => get_pending_state_overrides
+        getStateOverrideQuorum => state_override_quorum
+        getStateOverrideExpiryEpochs => state_override_expiry_epochs
+        getStateOverrideApprovals => state_override_approvals
+        setClaimHistorySize => set_claim_history_size
+        getClaimHistory => get_claim_history
+        getClaimHistorySize => get_claim_history_size
+        getClaimBitmap => get_claim_bitmap
+        issueBadgeCollection => issue_badge_collection
+        setBadgeTiers => set_badge_tiers
+        getBadge => get_badge
+        getBadgeTiers => get_badge_tiers
+        getBadgeTokenId => badge_token
+        getBadgeNonce => badge_nonce
+        setReferralMilestones => set_referral_milestones
+        getReferees => get_referees
+        getRefereeCount => get_referee_count
+        getReferralMilestones => get_referral_milestones
+        getReferrer => get_referrer
+        getReferralPoints => referral_points
+        proposeConfigChange => propose_config_change
+        executeConfigChange => execute_config_change
+        cancelConfigChange => cancel_config_change
+        getTimelockEpochs => get_timelock_epochs
+        getPendingConfigChanges => get_pending_config_changes
+        getConfigChange => get_config_change
     )
 }
 
-multiversx_sc_wasm_adapter::async_callback_empty! {}
+multiversx_sc_wasm_adapter::async_callback! { on_chain_claim }